@@ -0,0 +1,136 @@
+use std::time::{Duration, Instant};
+
+use crate::{Context, Node, Rng};
+
+/// Random moves around a fulfilled node for [`Anneal`]
+pub trait Neighbourhood<const B: usize>: Node<B> {
+    /// Random perturbation of a complete solution, `None` if there is none to try
+    fn perturb(&self, rng: &mut Rng) -> Option<Self>;
+}
+
+/// Limits of an [`Anneal`] or [`crate::Mcts`] run, whichever is hit first
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Budget {
+    pub(crate) iterations: usize,
+    pub(crate) time: Option<Duration>,
+}
+
+impl Budget {
+    /// Spend up to `iterations`
+    pub const fn new(iterations: usize) -> Self {
+        Self {
+            iterations,
+            time: None,
+        }
+    }
+
+    /// Stop after `time` has passed
+    pub const fn with_time(mut self, time: Duration) -> Self {
+        self.time = Some(time);
+        self
+    }
+
+    /// Iterations left to spend since `start`
+    pub(crate) fn iter(self, start: Instant) -> impl Iterator<Item = usize> {
+        (0..self.iterations).take_while(move |_| self.time.is_none_or(|t| start.elapsed() < t))
+    }
+}
+
+/// Acceptance statistics of [`Anneal`] runs
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Stats {
+    /// Perturbations tried
+    pub iterations: usize,
+    /// Perturbations moved to, including worsening ones
+    pub accepted: usize,
+    /// Perturbations improving the best node
+    pub improved: usize,
+    /// Perturbations refused by acceptance criterion
+    pub rejected: usize,
+    /// Perturbations missing or not fulfilled
+    pub infeasible: usize,
+}
+
+/// Simulated annealing and hill climbing refinement of fulfilled nodes
+#[derive(Debug, Clone)]
+pub struct Anneal {
+    temperature: f64,
+    cooling: f64,
+    rng: Rng,
+    seed: u64,
+    stats: Stats,
+}
+
+impl Anneal {
+    /// Simulated annealing starting at `temperature`, multiplied by `cooling` every iteration
+    ///
+    /// A move worsening score by `d` is accepted with `exp(-d / temperature)` probability
+    pub fn new(temperature: f64, cooling: f64, seed: u64) -> Self {
+        Self {
+            temperature,
+            cooling,
+            rng: Rng::new(seed),
+            seed,
+            stats: Stats::default(),
+        }
+    }
+
+    /// Hill climbing, only moves not worsening score are accepted
+    pub fn hill_climb(seed: u64) -> Self {
+        Self::new(0.0, 1.0, seed)
+    }
+
+    /// Statistics accumulated over all runs
+    #[inline]
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+
+    #[inline]
+    fn accept(&mut self, current: u64, next: u64) -> bool {
+        let d = next as f64 - current as f64;
+        (d <= 0.0) || (self.rng.unit() < (-d / self.temperature).exp())
+    }
+
+    /// Refine fulfilled `node` within `budget`, returns the best node visited
+    ///
+    /// Nodes are scored at depth `0` in a context counting iterations of all runs
+    pub fn run<const B: usize, T>(&mut self, node: T, budget: Budget) -> T
+    where
+        T: Neighbourhood<B> + Clone,
+    {
+        let start = Instant::now();
+
+        let seed = self.seed;
+        let ctx = |cycle| Context::new(0, cycle, 0).with_seed(seed);
+        let mut score = node.evaluate(&ctx(self.stats.iterations));
+        let mut best = (score, node.clone());
+        let mut current = node;
+
+        for _ in budget.iter(start) {
+            self.stats.iterations += 1;
+            let ctx = ctx(self.stats.iterations);
+
+            let Some(next) = current.perturb(&mut self.rng).filter(|n| n.has_fulfilled()) else {
+                self.stats.infeasible += 1;
+                continue;
+            };
+
+            let k = next.evaluate(&ctx);
+            if self.accept(score, k) {
+                self.stats.accepted += 1;
+                if k < best.0 {
+                    self.stats.improved += 1;
+                    best = (k, next.clone());
+                }
+                (score, current) = (k, next);
+            } else {
+                self.stats.rejected += 1;
+            }
+
+            self.temperature *= self.cooling;
+        }
+
+        best.1
+    }
+}
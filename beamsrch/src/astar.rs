@@ -0,0 +1,166 @@
+use alloc::{boxed::Box, collections::BTreeMap};
+use core::mem::MaybeUninit;
+
+use crate::{BeamError, Context, Node};
+
+/// Split of [`Node::evaluate`] into path cost and remaining estimate for [`AStar::weighted`]
+pub trait Heuristic<const B: usize>: Node<B> {
+    /// Cost accumulated on the path from root
+    fn cost(&self) -> u64;
+
+    /// Estimated cost left to fulfillment
+    fn heuristic(&self) -> u64;
+}
+
+/// Best-first (A*) tree search over [`Node`] with a bounded open list
+///
+/// With an unbounded open list and admissible scoring the first fulfilled node popped
+/// is optimal; bounding trades that for memory by dropping the worst scored open nodes
+#[derive(Debug)]
+pub struct AStar<const B: usize, T>
+where
+    T: Node<B>,
+{
+    /// Open nodes with their depth keyed by `(score, order)`
+    open: BTreeMap<(u64, usize), (usize, T)>,
+    cap: usize,
+    score: fn(&T, &Context, u64) -> u64,
+    /// Heuristic weight in 32.32 fixed point
+    weight: u64,
+    /// Pushed nodes count, breaks ties in insertion order
+    order: usize,
+    /// Expanded nodes count
+    expanded: usize,
+    node_buf: Box<[MaybeUninit<T>]>,
+}
+
+impl<const B: usize, T> AStar<B, T>
+where
+    T: Node<B>,
+{
+    fn with_score(root: T, cap: usize, score: fn(&T, &Context, u64) -> u64, weight: u64) -> Self {
+        Self {
+            // root is popped first regardless of its score
+            open: BTreeMap::from([((0, 0), (0, root))]),
+            cap: cap.max(1),
+            score,
+            weight,
+            order: 1,
+            expanded: 0,
+            node_buf: Box::new_uninit_slice(B),
+        }
+    }
+
+    /// Search from `root` using [`Node::evaluate`] as f-score, keeping up to `cap` open nodes
+    pub fn new(root: T, cap: usize) -> Self {
+        Self::with_score(root, cap, |n, ctx, _| n.evaluate(ctx), 1 << 32)
+    }
+
+    /// Search from `root` using `cost + weight * heuristic` as f-score, keeping up to
+    /// `cap` open nodes
+    ///
+    /// `weight` above `1.0` bounds suboptimality of an admissible heuristic by the same factor.
+    /// It is applied in fixed point with `2^-32` precision, so f-scores stay exact over the
+    /// whole `u64` range and saturate instead of overflowing
+    pub fn weighted(root: T, cap: usize, weight: f64) -> Self
+    where
+        T: Heuristic<B>,
+    {
+        Self::with_score(
+            root,
+            cap,
+            |n, _, w| {
+                let h = (n.heuristic() as u128 * w as u128) >> 32;
+                n.cost().saturating_add(h.try_into().unwrap_or(u64::MAX))
+            },
+            (weight * (1u64 << 32) as f64 + 0.5) as u64,
+        )
+    }
+
+    /// Open nodes count
+    #[inline]
+    pub fn open(&self) -> usize {
+        self.open.len()
+    }
+
+    /// Expanded nodes count
+    #[inline]
+    pub fn expanded(&self) -> usize {
+        self.expanded
+    }
+
+    /// Open `nodes` found in `ctx`
+    fn push<I: IntoIterator<Item = T>>(&mut self, ctx: Context, nodes: I) {
+        nodes.into_iter().enumerate().for_each(|(i, n)| {
+            let score = (self.score)(&n, &ctx.at(i), self.weight);
+            self.open.insert((score, self.order), (ctx.depth, n));
+            self.order += 1;
+        });
+
+        while self.open.len() > self.cap {
+            self.open.pop_last();
+        }
+    }
+
+    /// Pop the best open node: return it if fulfilled, otherwise expand it
+    #[cfg(not(feature = "rayon"))]
+    pub fn step(&mut self) -> Result<Option<T>, BeamError> {
+        let (_, (depth, node)) = self.open.pop_first().ok_or(BeamError::Exhausted)?;
+        if node.has_fulfilled() {
+            return Ok(Some(node));
+        }
+
+        let ctx = Context::new(depth, self.expanded, 0);
+        self.expanded += 1;
+        // dead ends are dropped
+        if let Ok(nodes) = crate::successors(&node, &ctx, &mut self.node_buf) {
+            self.push(ctx.child(), nodes);
+        }
+
+        Ok(None)
+    }
+
+    /// Pop the best open node: return it if fulfilled, otherwise expand it
+    #[cfg(feature = "rayon")]
+    pub fn step(&mut self) -> Result<Option<T>, BeamError>
+    where
+        T: Send,
+    {
+        let (_, (depth, node)) = self.open.pop_first().ok_or(BeamError::Exhausted)?;
+        if node.has_fulfilled() {
+            return Ok(Some(node));
+        }
+
+        let ctx = Context::new(depth, self.expanded, 0);
+        self.expanded += 1;
+        // dead ends are dropped
+        if let Ok(nodes) = crate::successors(&node, &ctx, &mut self.node_buf) {
+            self.push(ctx.child(), nodes);
+        }
+
+        Ok(None)
+    }
+
+    /// Step until a fulfilled node is found or the open list is exhausted
+    #[cfg(not(feature = "rayon"))]
+    pub fn run(&mut self) -> Result<T, BeamError> {
+        loop {
+            if let Some(node) = self.step()? {
+                break Ok(node);
+            }
+        }
+    }
+
+    /// Step until a fulfilled node is found or the open list is exhausted
+    #[cfg(feature = "rayon")]
+    pub fn run(&mut self) -> Result<T, BeamError>
+    where
+        T: Send,
+    {
+        loop {
+            if let Some(node) = self.step()? {
+                break Ok(node);
+            }
+        }
+    }
+}
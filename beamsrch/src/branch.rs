@@ -0,0 +1,93 @@
+use alloc::vec::Vec;
+
+/// Best, runner-up and worst of successor selection keys
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Spread {
+    /// Best key with its successor index, ties broken by lower index
+    pub(crate) best: ((bool, u64), usize),
+    second: u64,
+    worst: u64,
+}
+
+impl Spread {
+    /// Identity of [`Spread::merge`]
+    pub(crate) const EMPTY: Self = Self {
+        best: ((true, u64::MAX), usize::MAX),
+        second: u64::MAX,
+        worst: 0,
+    };
+
+    #[inline]
+    pub(crate) fn one(j: usize, key: (bool, u64)) -> Self {
+        Self {
+            best: (key, j),
+            second: u64::MAX,
+            worst: key.1,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn merge(self, other: Self) -> Self {
+        let (lo, hi) = match self.best <= other.best {
+            true => (self, other),
+            false => (other, self),
+        };
+        Self {
+            best: lo.best,
+            second: lo.second.min(hi.best.0.1),
+            worst: lo.worst.max(hi.worst),
+        }
+    }
+
+    /// Successor limit following `limit`, clamped to `1..=cap`
+    ///
+    /// Narrows when the best successor dominates the rest, widens when the runner-up
+    /// is close to it or there was nothing to compare against
+    pub(crate) fn adapt(&self, limit: usize, cap: usize) -> usize {
+        let best = self.best.0.1;
+        let next = match self.second {
+            u64::MAX => limit.saturating_mul(2),
+            second => {
+                let gap = second.saturating_sub(best) as u128;
+                let range = self.worst.saturating_sub(best) as u128 + 1;
+                if 2 * gap > range {
+                    limit / 2
+                } else if 8 * gap < range {
+                    limit.saturating_mul(2)
+                } else {
+                    limit
+                }
+            }
+        };
+        next.clamp(1, cap)
+    }
+}
+
+/// Per-slot successor limits kept within a budget, see [`crate::Beam::with_branching`]
+#[derive(Debug)]
+pub(crate) struct Branching {
+    /// Successors allowed per cycle across all slots
+    budget: usize,
+    /// Limits by slot, seeded for slots past its end
+    pub(crate) limits: Vec<usize>,
+}
+
+impl Branching {
+    pub(crate) fn new(budget: usize) -> Self {
+        Self {
+            budget: budget.max(1),
+            limits: Vec::new(),
+        }
+    }
+
+    /// Keep adapted `limits`, scaled down to fit the budget
+    pub(crate) fn update(&mut self, mut limits: Vec<usize>) {
+        let total = limits.iter().sum::<usize>();
+        if total > self.budget {
+            limits
+                .iter_mut()
+                .for_each(|l| *l = (*l * self.budget / total).max(1));
+        }
+        self.limits = limits;
+    }
+}
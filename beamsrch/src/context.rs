@@ -0,0 +1,59 @@
+use crate::Rng;
+
+/// Search position passed to [`crate::Node::expand`] and [`crate::Node::evaluate`]
+///
+/// Drivers fill what applies to them: [`crate::Beam`] counts cycles and slots, tree
+/// searches count steps and leave `slot` as sibling index or `0`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Context {
+    /// Levels below the search root
+    pub depth: usize,
+    /// Driver cycle or step count
+    pub cycle: usize,
+    /// Beam slot or sibling index
+    pub slot: usize,
+    seed: u64,
+}
+
+impl Context {
+    #[inline]
+    pub const fn new(depth: usize, cycle: usize, slot: usize) -> Self {
+        Self {
+            depth,
+            cycle,
+            slot,
+            seed: 0,
+        }
+    }
+
+    /// Set search-wide `seed` mixed into [`Context::rng`]
+    #[inline]
+    pub const fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Same position one level deeper
+    #[inline]
+    pub const fn child(self) -> Self {
+        Self {
+            depth: self.depth + 1,
+            ..self
+        }
+    }
+
+    /// Same position in another `slot`
+    #[inline]
+    pub const fn at(self, slot: usize) -> Self {
+        Self { slot, ..self }
+    }
+
+    /// Generator derived from seed and position only, equal contexts yield equal streams
+    #[inline]
+    pub fn rng(&self) -> Rng {
+        let seed = [self.depth, self.cycle, self.slot]
+            .into_iter()
+            .fold(self.seed, |s, x| Rng::new(s ^ x as u64).next_u64());
+        Rng::new(seed)
+    }
+}
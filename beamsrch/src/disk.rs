@@ -0,0 +1,309 @@
+use alloc::{boxed::Box, vec::Vec};
+use core::{fmt, mem::MaybeUninit, ops::Range};
+use std::{fs::File, io};
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use sprsdata::ext::{BufMut, Mode};
+
+use crate::{BeamError, Context, Node};
+
+/// Binary form of a node for [`DiskBeam`] slots, of any length
+pub trait Encode: Sized {
+    /// Encoded length in bytes
+    fn size(&self) -> usize;
+
+    /// Write node into `buf` of [`Encode::size`] bytes
+    fn encode(&self, buf: &mut [u8]);
+
+    /// Read node back from `buf` holding its [`Encode::size`] bytes
+    fn decode(buf: &[u8]) -> Self;
+}
+
+/// Granularity of file growth in bytes
+const PAGE: usize = 4096;
+
+/// Scored successors of a slot in best scored first order
+type Ranked<T> = Result<Vec<(u64, T)>, BeamError>;
+
+/// Beam with slot heads kept encoded in a memory-mapped file
+///
+/// Slots are streamed through expansion `chunk` at a time, so only `chunk` heads and
+/// their successors are held in memory. Each slot is replaced by its best scored
+/// successor as in [`crate::Beam::cycle`]; free slots up to `capacity` are taken by
+/// runner-up successors in slot order
+///
+/// Heads are appended to the file, grown as needed, and located by an offset table;
+/// records of replaced heads are compacted away once they outweigh live ones
+pub struct DiskBeam<const B: usize, T>
+where
+    T: Node<B> + Encode,
+{
+    file: File,
+    slots: BufMut<u8>,
+    /// Byte range of every live slot head in `slots`
+    records: Vec<Range<usize>>,
+    /// End of the last written record
+    end: usize,
+    capacity: usize,
+    chunk: usize,
+    /// Performed cycles count
+    depth: usize,
+    /// Seed of [`Context::rng`] handed to nodes
+    seed: u64,
+    /// `chunk` expansion buffers of `B` nodes
+    node_buf: Box<[MaybeUninit<T>]>,
+}
+
+impl<const B: usize, T> fmt::Debug for DiskBeam<B, T>
+where
+    T: Node<B> + Encode,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DiskBeam")
+            .field("capacity", &self.capacity)
+            .field("width", &self.width())
+            .field("end", &self.end)
+            .field("chunk", &self.chunk)
+            .field("depth", &self.depth)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<const B: usize, T> DiskBeam<B, T>
+where
+    T: Node<B> + Encode,
+{
+    /// Lay unfulfilled `roots` out in `file`, resized as they are written, taking up to
+    /// `capacity` slots
+    ///
+    /// Fails with [`BeamError::Fulfilled`] wrapped into [`io::Error`] if every root has
+    /// fulfilled, with [`BeamError::NoSuccessors`] if no root is given
+    pub fn from_roots<I: IntoIterator<Item = T>>(
+        file: &File,
+        roots: I,
+        capacity: usize,
+        chunk: usize,
+    ) -> io::Result<Self> {
+        let capacity = capacity.max(1);
+        let chunk = chunk.clamp(1, capacity);
+
+        let file = file.try_clone()?;
+        file.set_len(PAGE as u64)?;
+        let slots = BufMut::<u8>::new(&file, Mode::Shared, 0, PAGE)?;
+
+        let mut beam = Self {
+            file,
+            slots,
+            records: Vec::with_capacity(capacity),
+            end: 0,
+            capacity,
+            chunk,
+            depth: 0,
+            seed: 0,
+            node_buf: Box::new_uninit_slice(chunk * B),
+        };
+        let mut given = false;
+        roots
+            .into_iter()
+            .inspect(|_| given = true)
+            .filter(|r| !r.has_fulfilled())
+            .take(capacity)
+            .try_for_each(|r| beam.push(&r))?;
+
+        match (beam.width(), given) {
+            (0, true) => Err(io::Error::other(BeamError::Fulfilled)),
+            (0, false) => Err(io::Error::other(BeamError::NoSuccessors)),
+            _ => Ok(beam),
+        }
+    }
+
+    /// Set `seed` of [`Context::rng`] handed to nodes from now on
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Context of live `slot` heads in the upcoming cycle
+    #[inline]
+    pub fn context(&self, slot: usize) -> Context {
+        Context::new(self.depth, self.depth, slot).with_seed(self.seed)
+    }
+
+    /// Slots count the file holds
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Live slots count
+    #[inline]
+    pub fn width(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Performed cycles count
+    #[inline]
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Decode head of live `slot`
+    #[inline]
+    pub fn get(&self, slot: usize) -> Option<T> {
+        let r = self.records.get(slot)?;
+        Some(T::decode(&self.slots[r.clone()]))
+    }
+
+    /// Decode live slot heads one by one, fulfilled or not
+    pub fn iter(&self) -> impl Iterator<Item = T> {
+        self.records
+            .iter()
+            .map(|r| T::decode(&self.slots[r.clone()]))
+    }
+
+    pub fn has_fulfilled(&self) -> bool {
+        self.iter().any(|n| n.has_fulfilled())
+    }
+
+    /// Best scored fulfilled node
+    pub fn best(&self) -> Option<T> {
+        self.iter()
+            .enumerate()
+            .filter(|(_, n)| n.has_fulfilled())
+            .map(|(s, n)| (n.evaluate(&self.context(s)), s, n))
+            .min_by_key(|&(k, s, _)| (k, s))
+            .map(|(.., n)| n)
+    }
+
+    /// Append encoded `node`, growing the file if it runs out
+    fn write(&mut self, node: &T) -> io::Result<Range<usize>> {
+        let range = self.end..self.end + node.size();
+        if range.end > self.slots.len() {
+            let len = range.end.max(self.slots.len() * 2).next_multiple_of(PAGE);
+            self.file.set_len(len as u64)?;
+            self.slots = BufMut::<u8>::new(&self.file, Mode::Shared, 0, len)?;
+        }
+
+        node.encode(&mut self.slots[range.clone()]);
+        self.end = range.end;
+        Ok(range)
+    }
+
+    #[inline]
+    fn push(&mut self, node: &T) -> io::Result<()> {
+        let range = self.write(node)?;
+        self.records.push(range);
+        Ok(())
+    }
+
+    /// Move live records to the front once replaced ones outweigh them
+    fn compact(&mut self) {
+        let live = self.records.iter().map(|r| r.len()).sum::<usize>();
+        if self.end - live <= live {
+            return;
+        }
+
+        let mut order = (0..self.records.len()).collect::<Vec<_>>();
+        order.sort_unstable_by_key(|&s| self.records[s].start);
+        // records only move towards the front, past ones already moved
+        self.end = order.into_iter().fold(0, |at, s| {
+            let r = self.records[s].clone();
+            self.slots.copy_within(r.clone(), at);
+            self.records[s] = at..at + r.len();
+            at + r.len()
+        });
+    }
+
+    /// Successors of `node` found in `ctx`, best scored first
+    fn rank<F>(node: &T, ctx: &Context, buf: &mut [MaybeUninit<T>], successors: &F) -> Ranked<T>
+    where
+        F: Fn(&T, &Context, &mut [MaybeUninit<T>]) -> Result<Vec<T>, BeamError>,
+    {
+        let mut list = successors(node, ctx, buf)?
+            .into_iter()
+            .map(|n| (n.evaluate(&ctx.child()), n))
+            .collect::<Vec<_>>();
+        // stable, ties keep expansion order
+        list.sort_by_key(|&(k, _)| k);
+        core::hint::select_unpredictable(list.is_empty(), Err(BeamError::BranchExhausted), Ok(list))
+    }
+
+    /// Stream live slots through `expand` a chunk at a time
+    fn cycle_with<F>(&mut self, expand: F) -> io::Result<()>
+    where
+        F: Fn(&[T], &mut [MaybeUninit<T>], Context) -> Vec<Ranked<T>>,
+    {
+        let width = self.width();
+        let mut exhausted = true;
+
+        for start in (0..width).step_by(self.chunk) {
+            let heads = self.records[start..width.min(start + self.chunk)]
+                .iter()
+                .map(|r| T::decode(&self.slots[r.clone()]))
+                .collect::<Vec<_>>();
+            let ctx = self.context(start);
+            let ranked = expand(&heads, &mut self.node_buf, ctx);
+
+            for (s, r) in (start..).zip(ranked) {
+                exhausted &= matches!(r, Err(BeamError::BranchExhausted));
+                let Ok(list) = r else { continue };
+
+                let mut list = list.into_iter().map(|(_, n)| n);
+                if let Some(best) = list.next() {
+                    self.records[s] = self.write(&best)?;
+                }
+                // runner-ups take free slots, expanded from the next cycle on
+                for n in list.take(self.capacity - self.width()) {
+                    self.push(&n)?;
+                }
+            }
+        }
+        self.compact();
+        self.depth += 1;
+
+        match exhausted {
+            true => Err(io::Error::other(BeamError::Exhausted)),
+            false => Ok(()),
+        }
+    }
+
+    /// Replace every live slot head with its best scored successor
+    ///
+    /// Fails with [`BeamError::Exhausted`] wrapped into [`io::Error`] if every slot is
+    /// exhausted, or with the error growing the file
+    #[cfg(not(feature = "rayon"))]
+    pub fn cycle(&mut self) -> io::Result<()> {
+        self.cycle_with(|heads, node_buf, ctx| {
+            heads
+                .iter()
+                .zip(node_buf.chunks_exact_mut(B))
+                .enumerate()
+                .map(|(k, (node, buf))| {
+                    Self::rank(node, &ctx.at(ctx.slot + k), buf, &crate::successors)
+                })
+                .collect()
+        })
+    }
+
+    /// Replace every live slot head with its best scored successor
+    ///
+    /// Fails with [`BeamError::Exhausted`] wrapped into [`io::Error`] if every slot is
+    /// exhausted, or with the error growing the file
+    #[cfg(feature = "rayon")]
+    pub fn cycle(&mut self) -> io::Result<()>
+    where
+        T: Send + Sync,
+    {
+        self.cycle_with(|heads, node_buf, ctx| {
+            heads
+                .par_iter()
+                .zip(node_buf.par_chunks_exact_mut(B))
+                .enumerate()
+                .map(|(k, (node, buf))| {
+                    Self::rank(node, &ctx.at(ctx.slot + k), buf, &crate::successors)
+                })
+                .collect()
+        })
+    }
+}
@@ -0,0 +1,103 @@
+use alloc::vec::Vec;
+
+use crate::{Context, Node};
+
+/// Criterion telling kept nodes apart
+#[derive(Debug, Clone, Copy)]
+enum Distinct<T> {
+    Equality(fn(&T, &T) -> bool),
+    Signature(fn(&T) -> u64),
+}
+
+/// Bounded collection of the best scored distinct fulfilled nodes
+#[derive(Debug, Clone)]
+pub struct KBest<T> {
+    k: usize,
+    distinct: Distinct<T>,
+    /// `(score, signature, node)` in best scored first order
+    list: Vec<(u64, u64, T)>,
+}
+
+impl<T> KBest<T> {
+    /// Keep up to `k` nodes distinct by [`PartialEq`]
+    pub fn new(k: usize) -> Self
+    where
+        T: PartialEq,
+    {
+        Self {
+            k,
+            distinct: Distinct::Equality(T::eq),
+            list: Vec::with_capacity(k),
+        }
+    }
+
+    /// Keep up to `k` nodes distinct by `signature`
+    pub fn with_signature(k: usize, signature: fn(&T) -> u64) -> Self {
+        Self {
+            k,
+            distinct: Distinct::Signature(signature),
+            list: Vec::with_capacity(k),
+        }
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    /// Kept `(score, node)` pairs in best scored first order
+    pub fn iter(&self) -> impl Iterator<Item = (u64, &T)> {
+        self.list.iter().map(|(k, _, n)| (*k, n))
+    }
+
+    /// Kept nodes in best scored first order
+    pub fn into_vec(self) -> Vec<T> {
+        self.list.into_iter().map(|(_, _, n)| n).collect()
+    }
+
+    /// Offer a copy of fulfilled `node` scored in `ctx`, returns whether it was kept
+    ///
+    /// A node equal to a kept one by [`KBest`] criterion replaces it only if scored better
+    pub fn offer<const B: usize>(&mut self, node: &T, ctx: &Context) -> bool
+    where
+        T: Node<B> + Clone,
+    {
+        if self.k == 0 || !node.has_fulfilled() {
+            return false;
+        }
+
+        let score = node.evaluate(ctx);
+        if self.list.len() == self.k && self.list.last().is_some_and(|&(k, ..)| score >= k) {
+            return false;
+        }
+
+        let signature = match self.distinct {
+            Distinct::Signature(f) => f(node),
+            Distinct::Equality(_) => 0,
+        };
+        let twin = self.list.iter().position(|(_, s, n)| match self.distinct {
+            Distinct::Signature(_) => *s == signature,
+            Distinct::Equality(f) => f(n, node),
+        });
+
+        match twin {
+            Some(i) if self.list[i].0 <= score => return false,
+            Some(i) => {
+                self.list.remove(i);
+            }
+            None if self.list.len() == self.k => {
+                self.list.pop();
+            }
+            None => {}
+        }
+
+        let i = self.list.partition_point(|&(k, ..)| k <= score);
+        self.list.insert(i, (score, signature, node.clone()));
+        true
+    }
+}
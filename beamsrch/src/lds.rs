@@ -0,0 +1,158 @@
+use alloc::{
+    boxed::Box,
+    vec::{self, Vec},
+};
+use core::mem::MaybeUninit;
+
+use crate::{BeamError, Context, Node};
+
+/// Successors of a node on the search path
+struct Frame<T> {
+    /// Scored successors in best scored first order
+    list: vec::IntoIter<(u64, T)>,
+    /// Rank of the next successor
+    rank: usize,
+    /// Discrepancies left for the path
+    left: usize,
+}
+
+/// Limited discrepancy search (LDS) over [`Node`]
+///
+/// Follows the best successor by [`Node::evaluate`], ties kept in [`Node::expand`] order;
+/// taking any of the next `breadth - 1` successors instead counts as a discrepancy.
+/// Memory is bounded by `breadth` nodes per level of the path
+#[derive(Debug)]
+pub struct Lds<const B: usize, T>
+where
+    T: Node<B>,
+{
+    root: T,
+    breadth: usize,
+    /// Expanded nodes count
+    expanded: usize,
+    node_buf: Box<[MaybeUninit<T>]>,
+}
+
+impl<const B: usize, T> Lds<B, T>
+where
+    T: Node<B>,
+{
+    /// Search from `root` considering up to `breadth` best successors of each node
+    pub fn new(root: T, breadth: usize) -> Self {
+        Self {
+            root,
+            breadth: breadth.max(1),
+            expanded: 0,
+            node_buf: Box::new_uninit_slice(B),
+        }
+    }
+
+    /// Expanded nodes count
+    #[inline]
+    pub fn expanded(&self) -> usize {
+        self.expanded
+    }
+
+    /// Context of a node `depth` levels down the path
+    #[inline]
+    fn context(&self, depth: usize) -> Context {
+        Context::new(depth, self.expanded, 0)
+    }
+
+    /// Order successors of a node `depth` levels down best scored first and keep
+    /// `breadth` of them
+    fn rank(&self, depth: usize, list: Vec<T>) -> Frame<T> {
+        let ctx = self.context(depth + 1);
+        let mut list = list
+            .into_iter()
+            .enumerate()
+            .map(|(i, n)| (n.evaluate(&ctx.at(i)), n))
+            .collect::<Vec<_>>();
+        // stable, ties keep expansion order
+        list.sort_by_key(|&(k, _)| k);
+        list.truncate(self.breadth);
+        Frame {
+            list: list.into_iter(),
+            rank: 0,
+            left: 0,
+        }
+    }
+
+    /// Depth-first search with `successors` expanding nodes
+    fn probe_with<F>(&mut self, d: usize, successors: F) -> Result<T, BeamError>
+    where
+        F: Fn(&T, &Context, &mut [MaybeUninit<T>]) -> Result<Vec<T>, BeamError>,
+    {
+        if self.root.has_fulfilled() {
+            return Err(BeamError::Fulfilled);
+        }
+
+        let list = successors(&self.root, &self.context(0), &mut self.node_buf);
+        let mut stack = Vec::from([Frame {
+            left: d,
+            ..self.rank(0, list.unwrap_or_default())
+        }]);
+        self.expanded += 1;
+
+        while let Some(frame) = stack.last_mut() {
+            let rank = frame.rank;
+            frame.rank += 1;
+
+            let next = frame.list.next().map(|(_, n)| n);
+            let Some(node) = next.filter(|_| (rank == 0) | (frame.left > 0)) else {
+                stack.pop();
+                continue;
+            };
+            if node.has_fulfilled() {
+                return Ok(node);
+            }
+
+            let left = frame.left - (rank > 0) as usize;
+            let depth = stack.len();
+            // dead ends are dropped
+            if let Ok(list) = successors(&node, &self.context(depth), &mut self.node_buf) {
+                let frame = self.rank(depth, list);
+                stack.push(Frame { left, ..frame });
+            }
+            self.expanded += 1;
+        }
+
+        Err(BeamError::Exhausted)
+    }
+
+    /// Find a fulfilled node on paths with at most `d` discrepancies
+    #[cfg(not(feature = "rayon"))]
+    pub fn probe(&mut self, d: usize) -> Result<T, BeamError> {
+        self.probe_with(d, crate::successors)
+    }
+
+    /// Find a fulfilled node on paths with at most `d` discrepancies
+    #[cfg(feature = "rayon")]
+    pub fn probe(&mut self, d: usize) -> Result<T, BeamError>
+    where
+        T: Send,
+    {
+        self.probe_with(d, crate::successors)
+    }
+
+    /// Probe with discrepancy limit growing from `0` up to `max`
+    #[cfg(not(feature = "rayon"))]
+    pub fn run(&mut self, max: usize) -> Result<T, BeamError> {
+        (0..=max)
+            .map(|d| self.probe(d))
+            .find(|r| !matches!(r, Err(BeamError::Exhausted)))
+            .unwrap_or(Err(BeamError::Exhausted))
+    }
+
+    /// Probe with discrepancy limit growing from `0` up to `max`
+    #[cfg(feature = "rayon")]
+    pub fn run(&mut self, max: usize) -> Result<T, BeamError>
+    where
+        T: Send,
+    {
+        (0..=max)
+            .map(|d| self.probe(d))
+            .find(|r| !matches!(r, Err(BeamError::Exhausted)))
+            .unwrap_or(Err(BeamError::Exhausted))
+    }
+}
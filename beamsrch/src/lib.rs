@@ -1,17 +1,90 @@
+#![no_std]
+
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+#[cfg(feature = "std")]
+mod anneal;
+mod astar;
+mod branch;
+mod context;
+#[cfg(feature = "memmap2")]
+mod disk;
+mod kbest;
+mod lds;
+#[cfg(feature = "std")]
+mod mcts;
+#[cfg(feature = "std")]
+mod restart;
+mod rng;
+mod tabu;
+pub mod testing;
 #[cfg(test)]
 mod tests;
+mod trace;
+#[cfg(feature = "std")]
+pub mod tune;
+
+use alloc::{boxed::Box, vec::Vec};
+use core::{fmt, mem::MaybeUninit};
 
-use std::mem::MaybeUninit;
+use branch::{Branching, Spread};
+
+use tabu::TabuList;
+use trace::Expansion;
+#[cfg(feature = "std")]
+use trace::Tracer;
 
 #[cfg(feature = "rayon")]
 use rayon::prelude::*;
 
+#[cfg(feature = "std")]
+pub use anneal::*;
+pub use astar::*;
+pub use context::*;
+#[cfg(feature = "memmap2")]
+pub use disk::*;
+pub use kbest::*;
+pub use lds::*;
+#[cfg(feature = "std")]
+pub use mcts::*;
+#[cfg(feature = "std")]
+pub use restart::*;
+pub use rng::*;
+pub use tabu::Tabu;
+pub use trace::Label;
+#[cfg(feature = "std")]
+pub use trace::Trace;
+
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub enum BeamError {
+    /// Node has no successors to expand into
     BranchExhausted,
+    /// Every beam slot has exhausted its branch
     Exhausted,
+    /// Root node has fulfilled before construction
+    Fulfilled,
+    /// Root node has expanded into no successors
+    NoSuccessors,
+    /// Previous cycle was interrupted by a panic in node code
+    Poisoned,
 }
 
+impl fmt::Display for BeamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::BranchExhausted => "node branch is exhausted",
+            Self::Exhausted => "every beam slot is exhausted",
+            Self::Fulfilled => "root node has already fulfilled",
+            Self::NoSuccessors => "root node has no successors",
+            Self::Poisoned => "beam is poisoned by a panic in a previous cycle",
+        })
+    }
+}
+
+impl core::error::Error for BeamError {}
+
 pub trait Node<const B: usize>
 where
     Self: Sized,
@@ -23,6 +96,7 @@ where
     /// Generate successor nodes and write them to buffer
     fn expand<'a, I: Iterator<Item = &'a mut MaybeUninit<Self>>>(
         &'a self,
+        ctx: &Context,
         iter: I,
     ) -> Result<usize, BeamError>;
 
@@ -30,22 +104,115 @@ where
     /// Generate successor nodes and write them to buffer (parallel)
     fn expand<'a, I: IndexedParallelIterator<Item = &'a mut MaybeUninit<Self>>>(
         &'a self,
+        ctx: &Context,
         iter: I,
     ) -> Result<usize, BeamError>;
 
     /// Node's score heuristics funciton for [`Beam::cycle`]
-    fn evaluate(&self) -> u64;
-
-    /// Mutate nodes in [`Beam::node_buf`] to meet criteria for [`Node::expand`]
-    fn inflate(&mut self) {
-        unimplemented!()
-    }
+    ///
+    /// Must score the same node equally for equal `ctx`
+    fn evaluate(&self, ctx: &Context) -> u64;
 
     fn estimate(&self) -> Option<usize> {
         None
     }
 }
 
+/// Relaxation of a node's constraints for [`Beam::extend`]
+pub trait Inflate<const B: usize>: Node<B> {
+    /// Mutate node so an exhausted branch can be expanded again with [`Node::expand`]
+    ///
+    /// Must keep [`Node::has_fulfilled`] status, see [`testing::check_inflate`]
+    fn inflate(&mut self);
+}
+
+/// Expand `node` into `buf` and move written successors out
+#[cfg(not(feature = "rayon"))]
+fn successors<const B: usize, T: Node<B>>(
+    node: &T,
+    ctx: &Context,
+    buf: &mut [MaybeUninit<T>],
+) -> Result<Vec<T>, BeamError> {
+    let i = node.expand(ctx, buf.iter_mut())?;
+    Ok(buf
+        .iter()
+        .take(i)
+        .map(|x| unsafe { x.assume_init_read() })
+        .collect())
+}
+
+/// Expand `node` into `buf` and move written successors out
+#[cfg(feature = "rayon")]
+fn successors<const B: usize, T: Node<B> + Send>(
+    node: &T,
+    ctx: &Context,
+    buf: &mut [MaybeUninit<T>],
+) -> Result<Vec<T>, BeamError> {
+    let i = node.expand(ctx, buf.par_iter_mut())?;
+    Ok(buf
+        .iter()
+        .take(i)
+        .map(|x| unsafe { x.assume_init_read() })
+        .collect())
+}
+
+/// Successors written to a slot buffer, dropped unless disarmed
+///
+/// Keeps a panic in [`Node::evaluate`] or in a node's drop from leaking or double
+/// dropping successors
+struct Successors<'a, T> {
+    buf: &'a mut [MaybeUninit<T>],
+    /// Initialized prefix of `buf`
+    len: usize,
+}
+
+impl<T> Drop for Successors<'_, T> {
+    fn drop(&mut self) {
+        self.buf
+            .iter_mut()
+            .take(self.len)
+            .for_each(|x| unsafe { x.assume_init_drop() });
+    }
+}
+
+/// Runner-up successors left in slot buffers to seed new slots, dropped unless disarmed
+///
+/// Keeps a panic in [`Node::evaluate`] while seeding from leaking runner-ups
+struct Runners<'a, T> {
+    buf: &'a mut [MaybeUninit<T>],
+    stride: usize,
+    /// Initialized successors count after every slot head, by slot
+    lens: Vec<usize>,
+}
+
+impl<'a, T> Runners<'a, T> {
+    #[inline]
+    fn disarm(mut self) -> &'a mut [MaybeUninit<T>] {
+        self.lens.clear();
+        core::mem::take(&mut self.buf)
+    }
+}
+
+impl<T> Drop for Runners<'_, T> {
+    fn drop(&mut self) {
+        let stride = self.stride;
+        self.lens.iter().enumerate().for_each(|(s, &n)| {
+            self.buf[s * stride + 1..=s * stride + n]
+                .iter_mut()
+                .for_each(|x| unsafe { x.assume_init_drop() })
+        });
+    }
+}
+
+/// Beam width as a function of cycle number, see [`Beam::with_schedule`]
+struct Schedule(Box<dyn Fn(usize) -> usize + Send + Sync>);
+
+impl fmt::Debug for Schedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Schedule").finish_non_exhaustive()
+    }
+}
+
 #[derive(Debug)]
 pub struct Beam<const W: usize, const B: usize, T>
 where
@@ -53,14 +220,47 @@ where
 {
     node_buf: Box<[MaybeUninit<T>]>,
     len: usize,
+    /// Live slots count, only heads of live slots are initialized
+    width: usize,
+    /// Performed cycles count
+    depth: usize,
+    /// Seed of [`Context::rng`] handed to nodes
+    seed: u64,
+    /// Set while a cycle runs, left set if it unwinds
+    poisoned: bool,
+    schedule: Option<Schedule>,
+    tabu: Option<TabuList<T>>,
+    branching: Option<Branching>,
+    #[cfg(feature = "std")]
+    trace: Option<Tracer<T>>,
 }
 
 #[cfg(not(feature = "rayon"))]
 impl<const W: usize, const B: usize, T> From<T> for Beam<W, B, T>
 where
-    T: Node<B> + Default,
+    T: Node<B>,
 {
     fn from(value: T) -> Self {
+        Self::expand_root(&value).unwrap()
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<const W: usize, const B: usize, T> From<T> for Beam<W, B, T>
+where
+    T: Node<B> + Send,
+{
+    fn from(value: T) -> Self {
+        Self::expand_root(&value).unwrap()
+    }
+}
+
+impl<const W: usize, const B: usize, T> Beam<W, B, T>
+where
+    T: Node<B>,
+{
+    #[cfg(not(feature = "rayon"))]
+    fn expand_root(root: &T) -> Result<Self, BeamError> {
         #[inline]
         fn nodes_mut<T>(
             node_buf: &mut Box<[MaybeUninit<T>]>,
@@ -71,27 +271,37 @@ where
                 .map(|c| c.first_mut().unwrap())
         }
 
-        let len = value.estimate().unwrap_or(W);
+        let len = match root.estimate() {
+            Some(0) => return Err(BeamError::NoSuccessors),
+            estimate => estimate.unwrap_or(W),
+        };
 
         let mut node_buf = Box::new_uninit_slice((len + 1) * W);
-        let count = value.expand(nodes_mut(&mut node_buf, len)).unwrap();
-        assert_ne!(count, 0);
-
-        // fill leftover nodes
-        nodes_mut(&mut node_buf, len).skip(count).for_each(|node| {
-            let _ = node.write(T::default());
-        });
+        let count = root.expand(&Context::default(), nodes_mut(&mut node_buf, len))?;
+        if count == 0 {
+            return Err(BeamError::NoSuccessors);
+        }
 
-        Self { node_buf, len }
+        Ok(Self {
+            node_buf,
+            len,
+            width: count,
+            depth: 0,
+            seed: 0,
+            poisoned: false,
+            schedule: None,
+            tabu: None,
+            branching: None,
+            #[cfg(feature = "std")]
+            trace: None,
+        })
     }
-}
 
-#[cfg(feature = "rayon")]
-impl<const W: usize, const B: usize, T> From<T> for Beam<W, B, T>
-where
-    T: Node<B> + Default + Send,
-{
-    fn from(value: T) -> Self {
+    #[cfg(feature = "rayon")]
+    fn expand_root(root: &T) -> Result<Self, BeamError>
+    where
+        T: Send,
+    {
         #[inline]
         fn nodes_mut<T: Send>(
             node_buf: &mut Box<[MaybeUninit<T>]>,
@@ -102,25 +312,283 @@ where
                 .map(|c| c.first_mut().unwrap())
         }
 
-        let len = value.estimate().unwrap_or(W);
+        let len = match root.estimate() {
+            Some(0) => return Err(BeamError::NoSuccessors),
+            estimate => estimate.unwrap_or(W),
+        };
+
+        let mut node_buf = Box::new_uninit_slice((len + 1) * W);
+        let count = root.expand(&Context::default(), nodes_mut(&mut node_buf, len))?;
+        if count == 0 {
+            return Err(BeamError::NoSuccessors);
+        }
+
+        Ok(Self {
+            node_buf,
+            len,
+            width: count,
+            depth: 0,
+            seed: 0,
+            poisoned: false,
+            schedule: None,
+            tabu: None,
+            branching: None,
+            #[cfg(feature = "std")]
+            trace: None,
+        })
+    }
+
+    /// Fallible counterpart of [`From`], rejects fulfilled and barren `root`
+    ///
+    /// Errors of [`Node::expand`] on `root` are passed through as is
+    #[cfg(not(feature = "rayon"))]
+    pub fn try_from_root(root: T) -> Result<Self, BeamError> {
+        if root.has_fulfilled() {
+            return Err(BeamError::Fulfilled);
+        }
+        Self::expand_root(&root)
+    }
+
+    /// Fallible counterpart of [`From`], rejects fulfilled and barren `root`
+    ///
+    /// Errors of [`Node::expand`] on `root` are passed through as is
+    #[cfg(feature = "rayon")]
+    pub fn try_from_root(root: T) -> Result<Self, BeamError>
+    where
+        T: Send,
+    {
+        if root.has_fulfilled() {
+            return Err(BeamError::Fulfilled);
+        }
+        Self::expand_root(&root)
+    }
+
+    /// Place unfulfilled `roots` into slot heads as is, rejects `roots` all fulfilled
+    fn with_roots<I: IntoIterator<Item = T>>(roots: I) -> Result<Self, BeamError> {
+        let len = W;
 
+        let mut given = false;
         let mut node_buf = Box::new_uninit_slice((len + 1) * W);
-        let count = value.expand(nodes_mut(&mut node_buf, len)).unwrap();
-        assert_ne!(count, 0);
+        let width = node_buf
+            .chunks_exact_mut(node_buf.len() / len)
+            .zip(
+                roots
+                    .into_iter()
+                    .inspect(|_| given = true)
+                    .filter(|r| !r.has_fulfilled()),
+            )
+            .map(|(c, root)| unsafe { c.first_mut().unwrap_unchecked() }.write(root))
+            .count();
+        if given & (width == 0) {
+            return Err(BeamError::Fulfilled);
+        }
+
+        Ok(Self {
+            node_buf,
+            len,
+            width,
+            depth: 0,
+            seed: 0,
+            poisoned: false,
+            schedule: None,
+            tabu: None,
+            branching: None,
+            #[cfg(feature = "std")]
+            trace: None,
+        })
+    }
 
-        // fill leftover nodes
-        nodes_mut(&mut node_buf, len).skip(count).for_each(|node| {
-            let _ = node.write(T::default());
+    /// Replace live roots with successors left in buffers, taking turns between roots
+    /// in best scored first order
+    fn distribute(&mut self, count: &[Result<usize, BeamError>]) {
+        let stride = self.node_buf.len() / self.len;
+        let node_buf = &self.node_buf;
+        // successors are laid out as slot heads of the first cycle
+        let ctx = self.context(0);
+
+        let mut pool = count
+            .iter()
+            .enumerate()
+            .flat_map(|(s, c)| {
+                let mut rank = (1..=*c.as_ref().unwrap_or(&0))
+                    .map(|j| s * stride + j)
+                    .map(|i| {
+                        let ctx = ctx.at(s);
+                        (unsafe { node_buf[i].assume_init_ref() }.evaluate(&ctx), i)
+                    })
+                    .collect::<Vec<_>>();
+                rank.sort_unstable();
+                rank.into_iter()
+                    .enumerate()
+                    .map(move |(r, (_, i))| (r, s, i))
+            })
+            .collect::<Vec<_>>();
+        pool.sort_unstable();
+
+        let width = core::mem::take(&mut self.width);
+        self.node_buf
+            .chunks_exact_mut(stride)
+            .take(width)
+            .for_each(|c| unsafe { c.first_mut().unwrap_unchecked().assume_init_drop() });
+
+        let n = self.len.min(pool.len());
+        let (seed, rest) = pool.split_at(n);
+        seed.iter().enumerate().for_each(|(s, &(_, _, i))| {
+            let node = unsafe { self.node_buf[i].assume_init_read() };
+            self.node_buf[s * stride].write(node);
         });
+        self.width = n;
 
-        Self { node_buf, len }
+        rest.iter()
+            .for_each(|&(_, _, i)| unsafe { self.node_buf[i].assume_init_drop() });
+    }
+
+    /// Construct from several `roots` spread across `W` slots
+    ///
+    /// Fulfilled roots are skipped; if the rest doesn't fill `W` slots, roots are replaced
+    /// with their successors, taking turns between roots in best scored first order.
+    /// Fails with [`BeamError::Fulfilled`] if every root has fulfilled and with
+    /// [`BeamError::NoSuccessors`] if no slot is left
+    #[cfg(not(feature = "rayon"))]
+    pub fn from_roots<I: IntoIterator<Item = T>>(roots: I) -> Result<Self, BeamError> {
+        let mut beam = Self::with_roots(roots)?;
+
+        if beam.width < beam.len {
+            let count = beam
+                .split_mut()
+                .enumerate()
+                .map(|(s, (node, buf))| node.expand(&Context::new(0, 0, s), buf.iter_mut()))
+                .collect::<Vec<_>>();
+            beam.distribute(&count);
+        }
+
+        core::hint::select_unpredictable(beam.width == 0, Err(BeamError::NoSuccessors), Ok(beam))
+    }
+
+    /// Construct from several `roots` spread across `W` slots
+    ///
+    /// Fulfilled roots are skipped; if the rest doesn't fill `W` slots, roots are replaced
+    /// with their successors, taking turns between roots in best scored first order.
+    /// Fails with [`BeamError::Fulfilled`] if every root has fulfilled and with
+    /// [`BeamError::NoSuccessors`] if no slot is left
+    #[cfg(feature = "rayon")]
+    pub fn from_roots<I: IntoIterator<Item = T>>(roots: I) -> Result<Self, BeamError>
+    where
+        T: Send,
+    {
+        let mut beam = Self::with_roots(roots)?;
+
+        if beam.width < beam.len {
+            let count = beam
+                .split_mut()
+                .enumerate()
+                .map(|(s, (node, buf))| node.expand(&Context::new(0, 0, s), buf.par_iter_mut()))
+                .collect::<Vec<_>>();
+            beam.distribute(&count);
+        }
+
+        core::hint::select_unpredictable(beam.width == 0, Err(BeamError::NoSuccessors), Ok(beam))
+    }
+
+    /// Set beam width `schedule` as a function of cycle number
+    ///
+    /// Width is clamped to `1..=len` slots allocated on construction: narrowing culls
+    /// the worst scored slots before expansion, widening seeds new slots with the best
+    /// runner-up successors after selection
+    pub fn with_schedule<F>(mut self, schedule: F) -> Self
+    where
+        F: Fn(usize) -> usize + Send + Sync + 'static,
+    {
+        self.schedule = Some(Schedule(Box::new(schedule)));
+        self
+    }
+
+    /// Set `seed` of [`Context::rng`] handed to nodes from now on
+    ///
+    /// Expansions made on construction see seed `0`
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Context of live `slot` heads in the upcoming cycle
+    ///
+    /// Depth is counted in cycles from the slots laid out on construction
+    #[inline]
+    pub fn context(&self, slot: usize) -> Context {
+        Context::new(self.depth, self.depth, slot).with_seed(self.seed)
+    }
+
+    /// Keep `attribute` of picked successors tabu for `tenure` cycles across all slots
+    ///
+    /// Successors sharing a tabu attribute are treated by `rule` during selection,
+    /// nodes without attribute are never tabu
+    pub fn with_tabu(
+        mut self,
+        tenure: usize,
+        rule: Tabu,
+        attribute: fn(&T) -> Option<u64>,
+    ) -> Self {
+        self.tabu = Some(TabuList::new(tenure, rule, attribute));
+        self
+    }
+
+    /// Adapt successors requested per slot to their score spread, `budget` successors
+    /// per cycle in total
+    ///
+    /// A slot asks for [`Node::estimate`] successors at first, then for twice as many
+    /// when its successors score close together and half as many when the best one
+    /// dominates, up to the slot buffer size. Limits exceeding `budget` are scaled down
+    /// proportionally, leaving every slot at least one successor
+    pub fn with_branching(mut self, budget: usize) -> Self {
+        self.branching = Some(Branching::new(budget));
+        self
+    }
+
+    /// Successor limits of live slots for the upcoming cycle, if branching adapts
+    ///
+    /// Empty until the first cycle and after slots were reordered
+    pub fn branching(&self) -> Option<&[usize]> {
+        self.branching.as_ref().map(|b| b.limits.as_slice())
+    }
+
+    /// Write what every cycle did to `out` in `format`, nodes labelled by `label`
+    ///
+    /// Per slot: its head, scored successors, the one surviving as the next head and
+    /// those seeding new slots when widening; slots culled when narrowing are noted
+    #[cfg(feature = "std")]
+    pub fn with_trace<O>(mut self, out: O, format: Trace, label: Label<T>) -> Self
+    where
+        O: std::io::Write + Send + Sync + 'static,
+    {
+        self.trace = Some(Tracer::new(out, format, label));
+        self
+    }
+
+    /// Close trace output and stop tracing, returns the first write error
+    #[cfg(feature = "std")]
+    pub fn finish_trace(&mut self) -> std::io::Result<()> {
+        self.trace.take().map_or(Ok(()), |mut t| t.finish())
+    }
+
+    /// Live slots count
+    #[inline]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Performed cycles count
+    #[inline]
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Whether a cycle was interrupted by a panic, see [`Beam::cycle`]
+    #[inline]
+    pub fn is_poisoned(&self) -> bool {
+        self.poisoned
     }
-}
 
-impl<const W: usize, const B: usize, T> Beam<W, B, T>
-where
-    T: Node<B>,
-{
     #[cfg(not(feature = "rayon"))]
     #[inline]
     pub fn has_fulfilled(&self) -> bool {
@@ -141,6 +609,7 @@ where
     pub fn nodes(&self) -> impl Iterator<Item = &T> {
         self.node_buf
             .chunks_exact(self.node_buf.len() / self.len)
+            .take(self.width)
             .map(|c| unsafe { c.first().unwrap_unchecked().assume_init_ref() })
             .filter(|c| c.has_fulfilled())
     }
@@ -153,15 +622,104 @@ where
     {
         self.node_buf
             .par_chunks_exact(self.node_buf.len() / self.len)
+            .take(self.width)
             .map(|c| unsafe { c.first().unwrap_unchecked().assume_init_ref() })
             .filter(|c| c.has_fulfilled())
     }
 
+    /// Live slots as `(slot, score, node)`, fulfilled or not
+    #[cfg(not(feature = "rayon"))]
+    #[inline]
+    pub fn frontier(&self) -> impl Iterator<Item = (usize, u64, &T)> {
+        self.node_buf
+            .chunks_exact(self.node_buf.len() / self.len)
+            .take(self.width)
+            .map(|c| unsafe { c.first().unwrap_unchecked().assume_init_ref() })
+            .enumerate()
+            .map(|(i, n)| (i, n.evaluate(&self.context(i)), n))
+    }
+
+    /// Live slots as `(slot, score, node)`, fulfilled or not
+    #[cfg(feature = "rayon")]
+    #[inline]
+    pub fn frontier(&self) -> impl IndexedParallelIterator<Item = (usize, u64, &T)>
+    where
+        T: Sync,
+    {
+        self.node_buf
+            .par_chunks_exact(self.node_buf.len() / self.len)
+            .take(self.width)
+            .map(|c| unsafe { c.first().unwrap_unchecked().assume_init_ref() })
+            .enumerate()
+            .map(|(i, n)| (i, n.evaluate(&self.context(i)), n))
+    }
+
+    /// Move the best scored node out of beam, fulfilled or not
+    #[cfg(not(feature = "rayon"))]
+    pub fn take_best(&mut self) -> Option<T> {
+        let slot = self.frontier().min_by_key(|&(_, k, _)| k)?.0;
+        Some(self.take(slot))
+    }
+
+    /// Move the best scored node out of beam, fulfilled or not
+    #[cfg(feature = "rayon")]
+    pub fn take_best(&mut self) -> Option<T>
+    where
+        T: Sync,
+    {
+        let slot = self.frontier().min_by_key(|&(i, k, _)| (k, i))?.0;
+        Some(self.take(slot))
+    }
+
+    /// Offer copies of fulfilled live nodes to `best`
+    #[cfg(not(feature = "rayon"))]
+    pub fn harvest(&self, best: &mut KBest<T>)
+    where
+        T: Clone,
+    {
+        self.frontier()
+            .filter(|(_, _, n)| n.has_fulfilled())
+            .for_each(|(i, _, n)| {
+                best.offer::<B>(n, &self.context(i));
+            });
+    }
+
+    /// Offer copies of fulfilled live nodes to `best`
+    #[cfg(feature = "rayon")]
+    pub fn harvest(&self, best: &mut KBest<T>)
+    where
+        T: Clone + Sync,
+    {
+        self.frontier()
+            .filter(|(_, _, n)| n.has_fulfilled())
+            .collect::<Vec<_>>()
+            .into_iter()
+            .for_each(|(i, _, n)| {
+                best.offer::<B>(n, &self.context(i));
+            });
+    }
+
+    /// Move node of live `slot` out, last live slot takes its place
+    fn take(&mut self, slot: usize) -> T {
+        let stride = self.node_buf.len() / self.len;
+
+        let last = self.width - 1;
+        self.reordered(
+            &(0..last)
+                .map(|p| if p == slot { last } else { p })
+                .collect::<Vec<_>>(),
+        );
+        self.width -= 1;
+        self.node_buf.swap(slot * stride, self.width * stride);
+        unsafe { self.node_buf[self.width * stride].assume_init_read() }
+    }
+
     #[cfg(not(feature = "rayon"))]
     #[inline]
     fn nodes_mut(&mut self) -> impl Iterator<Item = &mut T> {
         self.node_buf
             .chunks_exact_mut(self.node_buf.len() / self.len)
+            .take(self.width)
             .map(|c| unsafe { c.first_mut().unwrap_unchecked().assume_init_mut() })
     }
 
@@ -173,6 +731,7 @@ where
     {
         self.node_buf
             .par_chunks_exact_mut(self.node_buf.len() / self.len)
+            .take(self.width)
             .map(|c| unsafe { c.first_mut().unwrap_unchecked().assume_init_mut() })
     }
 
@@ -181,6 +740,7 @@ where
     fn split_mut(&mut self) -> impl Iterator<Item = (&mut T, &mut [MaybeUninit<T>])> {
         self.node_buf
             .chunks_exact_mut(self.node_buf.len() / self.len)
+            .take(self.width)
             .map(|c| unsafe {
                 let (l, r) = c.split_first_mut().unwrap_unchecked();
                 (l.assume_init_mut(), r)
@@ -195,84 +755,475 @@ where
     {
         self.node_buf
             .par_chunks_exact_mut(self.node_buf.len() / self.len)
+            .take(self.width)
             .map(|c| unsafe {
                 let (l, r) = c.split_first_mut().unwrap_unchecked();
                 (l.assume_init_mut(), r)
             })
     }
 
+    /// Put `tabu` back, remembering heads of slots advanced according to `count`
+    fn remember(&mut self, tabu: Option<TabuList<T>>, count: &[Result<usize, BeamError>]) {
+        self.tabu = tabu.map(|mut tabu| {
+            let stride = self.node_buf.len() / self.len;
+            let heads = count
+                .iter()
+                .zip(self.node_buf.chunks_exact(stride))
+                .filter(|(c, _)| c.is_ok())
+                .map(|(_, c)| unsafe { c.first().unwrap_unchecked().assume_init_ref() });
+            tabu.record(heads, self.depth);
+            tabu
+        });
+    }
+
+    /// Label hook of the tracer, if tracing
+    #[inline]
+    fn tracing(&self) -> Option<Label<T>> {
+        #[cfg(feature = "std")]
+        return self.trace.as_ref().map(|t| t.label);
+        #[cfg(not(feature = "std"))]
+        None
+    }
+
+    /// Carry traced slot heads over a reorder, slot `p` is now old slot `order[p]`;
+    /// branching limits are forgotten
+    #[inline]
+    fn reordered(&mut self, order: &[usize]) {
+        #[cfg(feature = "std")]
+        if let Some(t) = &mut self.trace {
+            t.reorder(order);
+        }
+        #[cfg(not(feature = "std"))]
+        let _ = order;
+        if let Some(b) = &mut self.branching {
+            b.limits.clear();
+        }
+    }
+
+    /// Successor limits of live slots, seeded from [`Node::estimate`] for slots new
+    /// to branching
+    fn limits(&self) -> Vec<usize> {
+        let stride = self.node_buf.len() / self.len;
+        let cap = stride - 1;
+        let Some(b) = &self.branching else {
+            return alloc::vec![cap; self.width];
+        };
+
+        let seeded = self
+            .node_buf
+            .chunks_exact(stride)
+            .take(self.width)
+            .skip(b.limits.len())
+            .map(|c| unsafe { c.first().unwrap_unchecked().assume_init_ref() })
+            .map(|n| n.estimate().unwrap_or(cap).clamp(1, cap));
+        b.limits.iter().copied().chain(seeded).collect()
+    }
+
+    /// Keep adapted `limits` for the next cycle
+    #[inline]
+    fn branched(&mut self, limits: Vec<usize>) {
+        if let Some(b) = &mut self.branching {
+            b.update(limits);
+        }
+    }
+
+    /// Write slot expansions recorded during the current cycle
+    #[inline]
+    fn traced(&mut self, traces: Vec<Option<Expansion>>) {
+        #[cfg(feature = "std")]
+        if let Some(t) = &mut self.trace {
+            t.record(self.depth, traces);
+        }
+        #[cfg(not(feature = "std"))]
+        let _ = traces;
+    }
+
+    /// Write slots about to be culled from the current cycle
+    #[inline]
+    fn culled(&mut self, slots: &[usize]) {
+        #[cfg(feature = "std")]
+        if let Some(t) = &mut self.trace {
+            let stride = self.node_buf.len() / self.len;
+            let dropped = slots
+                .iter()
+                .map(|&s| {
+                    let node = unsafe { self.node_buf[s * stride].assume_init_ref() };
+                    (s, trace::label(node, t.label))
+                })
+                .collect();
+            t.dropped(self.depth, dropped);
+        }
+        #[cfg(not(feature = "std"))]
+        let _ = slots;
+    }
+
+    /// Width scheduled for the upcoming cycle
+    #[inline]
+    fn scheduled(&self) -> usize {
+        self.schedule
+            .as_ref()
+            .map_or(self.width, |s| (s.0)(self.depth).clamp(1, self.len))
+    }
+
+    /// Cull live slots down to `width` best scored ones
+    fn shrink(&mut self, width: usize, scores: &[u64]) {
+        let stride = self.node_buf.len() / self.len;
+
+        let mut rank = (0..self.width).collect::<Vec<_>>();
+        rank.sort_unstable_by_key(|&i| (scores[i], i));
+        self.culled(&rank[width..]);
+        self.reordered(&rank[..width]);
+
+        // `pos[i]` is a current position of slot `i`, `at[p]` is a slot at position `p`
+        let mut pos = (0..self.width).collect::<Vec<_>>();
+        let mut at = pos.clone();
+        rank.into_iter().take(width).enumerate().for_each(|(p, i)| {
+            let q = pos[i];
+            self.node_buf.swap(p * stride, q * stride);
+            pos[at[p]] = q;
+            pos[i] = p;
+            at.swap(p, q);
+        });
+
+        let live = core::mem::replace(&mut self.width, width);
+        self.node_buf
+            .chunks_exact_mut(stride)
+            .take(live)
+            .skip(width)
+            .for_each(|c| unsafe { c.first_mut().unwrap_unchecked().assume_init_drop() });
+    }
+
+    /// Seed slots up to `width` with the best runner-up successors left in buffers,
+    /// `lens` of them after every slot head; seeds are noted in slot `traces`
+    fn seed(&mut self, width: usize, lens: Vec<usize>, traces: &mut [Option<Expansion>]) {
+        let stride = self.node_buf.len() / self.len;
+        // evaluated before `depth` is advanced, as in `cycle`
+        let ctx = self.context(0);
+
+        let runners = Runners {
+            buf: &mut self.node_buf,
+            stride,
+            lens,
+        };
+        let mut pool = runners
+            .lens
+            .iter()
+            .enumerate()
+            .flat_map(|(s, &n)| (1..=n).map(move |j| (s, s * stride + j)))
+            .map(|(s, i)| {
+                let node = unsafe { runners.buf[i].assume_init_ref() };
+                (node.evaluate(&ctx.at(s).child()), i)
+            })
+            .collect::<Vec<_>>();
+        let buf = runners.disarm();
+
+        let n = (width - self.width).min(pool.len());
+        if let Some(k) = n.checked_sub(1) {
+            pool.select_nth_unstable(k);
+        }
+
+        let (seed, rest) = pool.split_at(n);
+        (self.width..).zip(seed).for_each(|(s, &(_, i))| {
+            if let Some(t) = &mut traces[i / stride] {
+                // the survivor took the last child's place
+                let p = i % stride - 1;
+                let child = match t.survivor == Some(p) {
+                    true => t.children.len() - 1,
+                    false => p,
+                };
+                t.seeded.push((child, s));
+            }
+            let node = unsafe { buf[i].assume_init_read() };
+            buf[s * stride].write(node);
+        });
+        self.width += n;
+
+        rest.iter()
+            .for_each(|&(_, i)| unsafe { buf[i].assume_init_drop() });
+    }
+
+    /// Replace every live slot head with its best scored successor
+    ///
+    /// A panic in node code poisons the beam: live nodes stay valid to inspect and
+    /// drop, further cycles fail with [`BeamError::Poisoned`]
     #[cfg(not(feature = "rayon"))]
     pub fn cycle(&mut self) -> Result<(), BeamError> {
-        let cond = self
+        if self.poisoned {
+            return Err(BeamError::Poisoned);
+        }
+        self.poisoned = true;
+
+        let width = self.scheduled();
+        if width < self.width {
+            let ctx = self.context(0);
+            let scores = self
+                .nodes_mut()
+                .enumerate()
+                .map(|(i, n)| n.evaluate(&ctx.at(i)))
+                .collect::<Vec<_>>();
+            self.shrink(width, &scores);
+        }
+        let grow = width > self.width;
+
+        let ctx = self.context(0);
+        let depth = self.depth;
+        let tabu = self.tabu.take();
+        let label = self.tracing();
+        let mut traces = (0..self.width).map(|_| None).collect::<Vec<_>>();
+        let mut limits = self.limits();
+        let adapt = self.branching.is_some();
+        let kept = self
             .split_mut()
-            .map(|(node, buf)| -> Result<(), BeamError> {
-                // expansion
-                let i = node.expand(buf.iter_mut())?;
-
-                // evaluation + selection
-                *node = unsafe {
-                    buf.iter()
-                        .take(i)
-                        .map(|x| x.assume_init_read()) // dropped
-                        .min_by_key(|k| k.evaluate())
-                        .unwrap_unchecked()
-                };
+            .enumerate()
+            .zip(traces.iter_mut())
+            .zip(limits.iter_mut())
+            .map(
+                |(((s, (node, buf)), trace), limit)| -> Result<Successors<'_, T>, BeamError> {
+                    let ctx = ctx.at(s);
+                    *trace = label.map(|f| Expansion {
+                        parent: trace::label(node, f),
+                        ..Default::default()
+                    });
+
+                    // expansion
+                    let cap = buf.len();
+                    let i = node.expand(&ctx, buf[..(*limit).min(cap)].iter_mut())?;
+                    if i == 0 {
+                        return Err(BeamError::BranchExhausted);
+                    }
+                    let mut buf = Successors { buf, len: i };
+                    if let (Some(f), Some(t)) = (label, trace.as_mut()) {
+                        t.children = buf.buf[..i]
+                            .iter()
+                            .map(|x| unsafe { x.assume_init_ref() })
+                            .map(|x| (trace::label(x, f), x.evaluate(&ctx.child())))
+                            .collect();
+                    }
 
-                Ok(())
+                    // evaluation + selection
+                    let spread = unsafe {
+                        buf.buf
+                            .iter()
+                            .take(i)
+                            .map(|x| {
+                                let (x, ctx) = (x.assume_init_ref(), ctx.child());
+                                let k = x.evaluate(&ctx);
+                                tabu.as_ref().map_or((false, k), |t| t.key(x, k, depth))
+                            })
+                            .enumerate()
+                            .map(|(j, k)| Spread::one(j, k))
+                            .fold(Spread::EMPTY, Spread::merge)
+                    };
+                    let j = spread.best.1;
+                    if adapt {
+                        *limit = spread.adapt(*limit, cap);
+                    }
+                    if let Some(t) = trace {
+                        t.survivor = Some(j);
+                    }
+                    buf.buf.swap(j, i - 1);
+                    buf.len -= 1;
+                    drop(core::mem::replace(node, unsafe {
+                        buf.buf[i - 1].assume_init_read()
+                    }));
+
+                    Ok(buf)
+                },
+            )
+            .collect::<Vec<_>>();
+
+        // runner-ups are dropped, or kept to seed new slots
+        let (count, lens) = kept
+            .into_iter()
+            .map(|r| match r {
+                Ok(mut buf) => {
+                    let n = buf.len;
+                    if grow {
+                        buf.len = 0;
+                    }
+                    (Ok(n), n)
+                }
+                Err(e) => (Err(e), 0),
             })
-            .fold(true, |acc, c| {
-                acc & matches!(c, Err(BeamError::BranchExhausted))
-            });
+            .unzip::<_, _, Vec<_>, Vec<_>>();
+        if grow {
+            self.seed(width, lens, &mut traces);
+        }
 
-        std::hint::select_unpredictable(cond, Err(BeamError::Exhausted), Ok(()))
+        self.remember(tabu, &count);
+        self.traced(traces);
+        self.branched(limits);
+        self.depth += 1;
+        self.poisoned = false;
+
+        let cond = count
+            .iter()
+            .all(|c| matches!(c, Err(BeamError::BranchExhausted)));
+        core::hint::select_unpredictable(cond, Err(BeamError::Exhausted), Ok(()))
     }
 
+    /// Replace every live slot head with its best scored successor
+    ///
+    /// A panic in node code poisons the beam: live nodes stay valid to inspect and
+    /// drop, further cycles fail with [`BeamError::Poisoned`]
     #[cfg(feature = "rayon")]
     pub fn cycle(&mut self) -> Result<(), BeamError>
     where
         T: Send,
     {
-        let cond = self
+        if self.poisoned {
+            return Err(BeamError::Poisoned);
+        }
+        self.poisoned = true;
+
+        let width = self.scheduled();
+        if width < self.width {
+            let ctx = self.context(0);
+            let scores = self
+                .nodes_mut()
+                .enumerate()
+                .map(|(i, n)| n.evaluate(&ctx.at(i)))
+                .collect::<Vec<_>>();
+            self.shrink(width, &scores);
+        }
+        let grow = width > self.width;
+
+        let ctx = self.context(0);
+        let depth = self.depth;
+        let tabu = self.tabu.take();
+        let label = self.tracing();
+        let mut traces = (0..self.width).map(|_| None).collect::<Vec<_>>();
+        let mut limits = self.limits();
+        let adapt = self.branching.is_some();
+        let kept = self
             .split_mut()
-            .map(|(node, buf)| -> Result<(), BeamError> {
-                // expansion
-                let i = node.expand(buf.par_iter_mut())?;
-
-                // evaluation + selection
-                *node = unsafe {
-                    buf.par_iter_mut()
-                        .take(i)
-                        .map(|x| x.assume_init_read()) // dropped
-                        .min_by_key(|k| k.evaluate())
-                        .unwrap_unchecked()
-                };
+            .enumerate()
+            .zip(traces.par_iter_mut())
+            .zip(limits.par_iter_mut())
+            .map(
+                |(((s, (node, buf)), trace), limit)| -> Result<Successors<'_, T>, BeamError> {
+                    let ctx = ctx.at(s);
+                    *trace = label.map(|f| Expansion {
+                        parent: trace::label(node, f),
+                        ..Default::default()
+                    });
 
-                Ok(())
-            })
-            .fold_with(true, |acc, c| {
-                acc && matches!(c, Err(BeamError::BranchExhausted))
+                    // expansion
+                    let cap = buf.len();
+                    let i = node.expand(&ctx, buf[..(*limit).min(cap)].par_iter_mut())?;
+                    if i == 0 {
+                        return Err(BeamError::BranchExhausted);
+                    }
+                    let mut buf = Successors { buf, len: i };
+                    if let (Some(f), Some(t)) = (label, trace.as_mut()) {
+                        t.children = buf.buf[..i]
+                            .iter()
+                            .map(|x| unsafe { x.assume_init_ref() })
+                            .map(|x| (trace::label(x, f), x.evaluate(&ctx.child())))
+                            .collect();
+                    }
+
+                    // evaluation + selection
+                    let spread = unsafe {
+                        buf.buf
+                            .par_iter_mut()
+                            .take(i)
+                            .map(|x| {
+                                let (x, ctx) = (x.assume_init_ref(), ctx.child());
+                                let k = x.evaluate(&ctx);
+                                tabu.as_ref().map_or((false, k), |t| t.key(x, k, depth))
+                            })
+                            .enumerate()
+                            .map(|(j, k)| Spread::one(j, k))
+                            .reduce(|| Spread::EMPTY, Spread::merge)
+                    };
+                    let j = spread.best.1;
+                    if adapt {
+                        *limit = spread.adapt(*limit, cap);
+                    }
+                    if let Some(t) = trace {
+                        t.survivor = Some(j);
+                    }
+                    buf.buf.swap(j, i - 1);
+                    buf.len -= 1;
+                    drop(core::mem::replace(node, unsafe {
+                        buf.buf[i - 1].assume_init_read()
+                    }));
+
+                    Ok(buf)
+                },
+            )
+            .collect::<Vec<_>>();
+
+        // runner-ups are dropped, or kept to seed new slots
+        let (count, lens) = kept
+            .into_iter()
+            .map(|r| match r {
+                Ok(mut buf) => {
+                    let n = buf.len;
+                    if grow {
+                        buf.len = 0;
+                    }
+                    (Ok(n), n)
+                }
+                Err(e) => (Err(e), 0),
             })
-            .reduce(|| true, |acc, c| acc && c);
+            .unzip::<_, _, Vec<_>, Vec<_>>();
+        if grow {
+            self.seed(width, lens, &mut traces);
+        }
+
+        self.remember(tabu, &count);
+        self.traced(traces);
+        self.branched(limits);
+        self.depth += 1;
+        self.poisoned = false;
 
-        std::hint::select_unpredictable(cond, Err(BeamError::Exhausted), Ok(()))
+        let cond = count
+            .par_iter()
+            .all(|c| matches!(c, Err(BeamError::BranchExhausted)));
+        core::hint::select_unpredictable(cond, Err(BeamError::Exhausted), Ok(()))
     }
 
+    /// Inflate every live slot head, fulfilled or not, so exhausted branches of a
+    /// partially complete beam can continue on the next [`Beam::cycle`]
+    ///
+    /// Placements made so far, width and depth are kept; fulfilled heads stay fulfilled
     #[cfg(not(feature = "rayon"))]
     #[inline]
-    pub fn extend(&mut self) {
+    pub fn extend(&mut self)
+    where
+        T: Inflate<B>,
+    {
         self.nodes_mut().for_each(|node| {
             node.inflate();
         });
     }
 
+    /// Inflate every live slot head, fulfilled or not, so exhausted branches of a
+    /// partially complete beam can continue on the next [`Beam::cycle`]
+    ///
+    /// Placements made so far, width and depth are kept; fulfilled heads stay fulfilled
     #[cfg(feature = "rayon")]
     #[inline]
     pub fn extend(&mut self)
     where
-        T: Send,
+        T: Inflate<B> + Send,
     {
         self.nodes_mut().for_each(|node| {
             node.inflate();
         });
     }
 }
+
+impl<const W: usize, const B: usize, T> Drop for Beam<W, B, T>
+where
+    T: Node<B>,
+{
+    fn drop(&mut self) {
+        self.node_buf
+            .chunks_exact_mut(self.node_buf.len() / self.len)
+            .take(self.width)
+            .for_each(|c| unsafe { c.first_mut().unwrap_unchecked().assume_init_drop() });
+    }
+}
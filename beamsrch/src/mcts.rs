@@ -0,0 +1,284 @@
+use alloc::{boxed::Box, vec::Vec};
+use core::{mem::MaybeUninit, ops::Range};
+use std::time::Instant;
+
+use crate::{BeamError, Budget, Context, Node, Rng};
+
+/// Successor choice of [`Mcts`] rollouts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rollout {
+    /// Uniformly random successor
+    Random,
+    /// Best scored successor by [`Node::evaluate`]
+    Greedy,
+}
+
+#[derive(Debug)]
+struct Vertex<T> {
+    node: T,
+    parent: Option<usize>,
+    depth: usize,
+    children: Range<usize>,
+    visits: u32,
+    reward: f64,
+    /// Successors were generated, no successors mark a terminal vertex
+    expanded: bool,
+    /// Subtree has nothing left to visit
+    exhausted: bool,
+}
+
+impl<T> Vertex<T> {
+    fn new(node: T, parent: Option<usize>, depth: usize) -> Self {
+        Self {
+            node,
+            parent,
+            depth,
+            children: 0..0,
+            visits: 0,
+            reward: 0.0,
+            expanded: false,
+            exhausted: false,
+        }
+    }
+}
+
+/// Monte Carlo tree search (UCT) over [`Node`]
+///
+/// Tree is grown with [`Node::expand`], leaves are scored by rolling out to a fulfilled
+/// node and rewarding its [`Node::evaluate`] normalized over scores seen so far
+#[derive(Debug)]
+pub struct Mcts<const B: usize, T>
+where
+    T: Node<B>,
+{
+    tree: Vec<Vertex<T>>,
+    exploration: f64,
+    rollout: Rollout,
+    rng: Rng,
+    seed: u64,
+    /// Best fulfilled node found with its score
+    best: Option<(u64, T)>,
+    /// Range of fulfilled scores seen
+    bounds: (u64, u64),
+    /// Performed iterations count
+    iterations: usize,
+    node_buf: Box<[MaybeUninit<T>]>,
+}
+
+impl<const B: usize, T> Mcts<B, T>
+where
+    T: Node<B>,
+{
+    /// Search from `root` with UCT `exploration` constant (`√2` is customary)
+    pub fn new(root: T, exploration: f64, rollout: Rollout, seed: u64) -> Self {
+        Self {
+            tree: Vec::from([Vertex::new(root, None, 0)]),
+            exploration,
+            rollout,
+            rng: Rng::new(seed),
+            seed,
+            best: None,
+            bounds: (u64::MAX, u64::MIN),
+            iterations: 0,
+            node_buf: Box::new_uninit_slice(B),
+        }
+    }
+
+    /// Performed iterations count
+    #[inline]
+    pub fn iterations(&self) -> usize {
+        self.iterations
+    }
+
+    /// Tree vertices count
+    #[inline]
+    pub fn size(&self) -> usize {
+        self.tree.len()
+    }
+
+    /// Best fulfilled node found so far
+    #[inline]
+    pub fn best(&self) -> Option<&T> {
+        self.best.as_ref().map(|(_, n)| n)
+    }
+
+    /// Move the best fulfilled node found out
+    #[inline]
+    pub fn into_best(self) -> Option<T> {
+        self.best.map(|(_, n)| n)
+    }
+
+    /// Context of a node `depth` levels down in the current iteration
+    #[inline]
+    fn context(&self, depth: usize) -> Context {
+        Context::new(depth, self.iterations, 0).with_seed(self.seed)
+    }
+
+    #[inline]
+    fn uct(&self, v: usize, ln: f64) -> f64 {
+        let x = &self.tree[v];
+        match x.visits {
+            0 => f64::INFINITY,
+            n => x.reward / n as f64 + self.exploration * (ln / n as f64).sqrt(),
+        }
+    }
+
+    /// Descend from root to an unexpanded vertex by UCT
+    fn select(&self) -> usize {
+        let mut v = 0;
+        while self.tree[v].expanded {
+            let ln = (self.tree[v].visits.max(1) as f64).ln();
+            let next = self.tree[v]
+                .children
+                .clone()
+                .filter(|&c| !self.tree[c].exhausted)
+                .max_by(|&a, &b| self.uct(a, ln).total_cmp(&self.uct(b, ln)));
+            match next {
+                Some(c) => v = c,
+                None => break,
+            }
+        }
+        v
+    }
+
+    /// Pick a rollout successor out of `list` found in `ctx`
+    fn pick(&mut self, ctx: Context, mut list: Vec<T>) -> Option<T> {
+        let i = match self.rollout {
+            Rollout::Random => self.rng.below(list.len()),
+            Rollout::Greedy => (0..list.len()).min_by_key(|&i| list[i].evaluate(&ctx.at(i)))?,
+        };
+        (i < list.len()).then(|| list.swap_remove(i))
+    }
+
+    /// Score `node` in `ctx` if fulfilled, keep it if it is the best one
+    fn score(&mut self, node: T, ctx: &Context) -> Option<u64> {
+        if !node.has_fulfilled() {
+            return None;
+        }
+
+        let k = node.evaluate(ctx);
+        self.bounds = (self.bounds.0.min(k), self.bounds.1.max(k));
+        if self.best.as_ref().is_none_or(|&(b, _)| k < b) {
+            self.best = Some((k, node));
+        }
+        Some(k)
+    }
+
+    /// Descend from `v` picking successors until a fulfilled node or a dead end
+    fn rollout<F>(&mut self, v: usize, successors: &F) -> Option<u64>
+    where
+        T: Clone,
+        F: Fn(&T, &Context, &mut [MaybeUninit<T>]) -> Result<Vec<T>, BeamError>,
+    {
+        let mut ctx = self.context(self.tree[v].depth);
+        if self.tree[v].node.has_fulfilled() {
+            return self.score(self.tree[v].node.clone(), &ctx);
+        }
+
+        let list = successors(&self.tree[v].node, &ctx, &mut self.node_buf).ok()?;
+        ctx = ctx.child();
+        let mut node = self.pick(ctx, list)?;
+        while !node.has_fulfilled() {
+            let list = successors(&node, &ctx, &mut self.node_buf).ok()?;
+            ctx = ctx.child();
+            node = self.pick(ctx, list)?;
+        }
+        self.score(node, &ctx)
+    }
+
+    fn backpropagate(&mut self, v: usize, score: Option<u64>) {
+        let (lo, hi) = self.bounds;
+        let reward = score.map_or(0.0, |k| 1.0 - (k - lo) as f64 / ((hi - lo) as f64 + 1.0));
+
+        let mut u = Some(v);
+        while let Some(x) = u {
+            let exhausted = self.tree[x].expanded
+                && self.tree[x]
+                    .children
+                    .clone()
+                    .all(|c| self.tree[c].exhausted);
+
+            let vertex = &mut self.tree[x];
+            vertex.visits += 1;
+            vertex.reward += reward;
+            vertex.exhausted = exhausted;
+            u = vertex.parent;
+        }
+    }
+
+    /// Select, expand, roll out and backpropagate once
+    fn step_with<F>(&mut self, successors: F) -> Result<(), BeamError>
+    where
+        T: Clone,
+        F: Fn(&T, &Context, &mut [MaybeUninit<T>]) -> Result<Vec<T>, BeamError>,
+    {
+        if self.tree[0].exhausted {
+            return Err(BeamError::Exhausted);
+        }
+
+        // selection + expansion, fulfilled nodes and dead ends become terminal vertices
+        let mut v = self.select();
+        if !self.tree[v].expanded {
+            if !self.tree[v].node.has_fulfilled() {
+                let depth = self.tree[v].depth;
+                let list = successors(&self.tree[v].node, &self.context(depth), &mut self.node_buf);
+                let start = self.tree.len();
+                self.tree.extend(
+                    list.unwrap_or_default()
+                        .into_iter()
+                        .map(|n| Vertex::new(n, Some(v), depth + 1)),
+                );
+                self.tree[v].children = start..self.tree.len();
+            }
+            self.tree[v].expanded = true;
+
+            let children = self.tree[v].children.clone();
+            if !children.is_empty() {
+                v = children.start + self.rng.below(children.len());
+            }
+        }
+
+        let score = self.rollout(v, &successors);
+        self.backpropagate(v, score);
+        self.iterations += 1;
+        Ok(())
+    }
+
+    /// Perform a single iteration
+    #[cfg(not(feature = "rayon"))]
+    pub fn step(&mut self) -> Result<(), BeamError>
+    where
+        T: Clone,
+    {
+        self.step_with(crate::successors)
+    }
+
+    /// Perform a single iteration
+    #[cfg(feature = "rayon")]
+    pub fn step(&mut self) -> Result<(), BeamError>
+    where
+        T: Clone + Send,
+    {
+        self.step_with(crate::successors)
+    }
+
+    /// Iterate within `budget` or until the tree is exhausted, returns the best fulfilled node
+    #[cfg(not(feature = "rayon"))]
+    pub fn run(&mut self, budget: Budget) -> Option<&T>
+    where
+        T: Clone,
+    {
+        let _ = budget.iter(Instant::now()).try_for_each(|_| self.step());
+        self.best()
+    }
+
+    /// Iterate within `budget` or until the tree is exhausted, returns the best fulfilled node
+    #[cfg(feature = "rayon")]
+    pub fn run(&mut self, budget: Budget) -> Option<&T>
+    where
+        T: Clone + Send,
+    {
+        let _ = budget.iter(Instant::now()).try_for_each(|_| self.step());
+        self.best()
+    }
+}
@@ -0,0 +1,121 @@
+use std::time::Instant;
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use crate::{Beam, Budget, Node, Rng, Stats};
+
+/// Iterated beam search: ruin the incumbent, recreate it with [`Beam`], keep the best
+///
+/// Every restart passes the incumbent through `perturb`, which partially destroys it into
+/// an unfulfilled node (e.g. drops the last placed items), and cycles a beam from there
+/// until it has fulfilled or exhausted
+#[derive(Debug, Clone)]
+pub struct Restart<F> {
+    perturb: F,
+    rng: Rng,
+    stats: Stats,
+}
+
+impl<F> Restart<F> {
+    pub fn new(perturb: F, seed: u64) -> Self {
+        Self {
+            perturb,
+            rng: Rng::new(seed),
+            stats: Stats::default(),
+        }
+    }
+
+    /// Statistics accumulated over all runs, [`Stats::infeasible`] counts restarts that
+    /// were not perturbed or not recreated into a fulfilled node
+    #[inline]
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+
+    /// Cycle `beam` until it has fulfilled or stagnated, returns the best fulfilled node
+    #[cfg(not(feature = "rayon"))]
+    fn recreate<const W: usize, const B: usize, T>(mut beam: Beam<W, B, T>) -> Option<(u64, T)>
+    where
+        T: Node<B>,
+    {
+        while !beam.has_fulfilled() && beam.cycle().is_ok() {}
+
+        let (slot, k, _) = beam
+            .frontier()
+            .filter(|(.., n)| n.has_fulfilled())
+            .min_by_key(|&(_, k, _)| k)?;
+        Some((k, beam.take(slot)))
+    }
+
+    /// Cycle `beam` until it has fulfilled or stagnated, returns the best fulfilled node
+    #[cfg(feature = "rayon")]
+    fn recreate<const W: usize, const B: usize, T>(mut beam: Beam<W, B, T>) -> Option<(u64, T)>
+    where
+        T: Node<B> + Send + Sync,
+    {
+        while !beam.has_fulfilled() && beam.cycle().is_ok() {}
+
+        let (slot, k, _) = beam
+            .frontier()
+            .filter(|(.., n)| n.has_fulfilled())
+            .min_by_key(|&(i, k, _)| (k, i))?;
+        Some((k, beam.take(slot)))
+    }
+
+    fn run_with<const B: usize, T, R>(&mut self, root: T, budget: Budget, recreate: R) -> Option<T>
+    where
+        T: Node<B>,
+        F: FnMut(&T, &mut Rng) -> Option<T>,
+        R: Fn(T) -> Option<(u64, T)>,
+    {
+        let start = Instant::now();
+
+        let mut best = recreate(root)?;
+        for _ in budget.iter(start) {
+            self.stats.iterations += 1;
+
+            let Some((k, next)) = (self.perturb)(&best.1, &mut self.rng).and_then(&recreate) else {
+                self.stats.infeasible += 1;
+                continue;
+            };
+
+            // plateau moves are accepted to drift off stagnated incumbents
+            if k <= best.0 {
+                self.stats.accepted += 1;
+                self.stats.improved += (k < best.0) as usize;
+                best = (k, next);
+            } else {
+                self.stats.rejected += 1;
+            }
+        }
+
+        Some(best.1)
+    }
+
+    /// Run a beam from unfulfilled `root`, then restart from the perturbed incumbent
+    /// within `budget`, returns the best fulfilled node found
+    #[cfg(not(feature = "rayon"))]
+    pub fn run<const W: usize, const B: usize, T>(&mut self, root: T, budget: Budget) -> Option<T>
+    where
+        T: Node<B>,
+        F: FnMut(&T, &mut Rng) -> Option<T>,
+    {
+        self.run_with::<B, T, _>(root, budget, |node| {
+            Self::recreate(Beam::<W, B, T>::try_from_root(node).ok()?)
+        })
+    }
+
+    /// Run a beam from unfulfilled `root`, then restart from the perturbed incumbent
+    /// within `budget`, returns the best fulfilled node found
+    #[cfg(feature = "rayon")]
+    pub fn run<const W: usize, const B: usize, T>(&mut self, root: T, budget: Budget) -> Option<T>
+    where
+        T: Node<B> + Send + Sync,
+        F: FnMut(&T, &mut Rng) -> Option<T>,
+    {
+        self.run_with::<B, T, _>(root, budget, |node| {
+            Self::recreate(Beam::<W, B, T>::try_from_root(node).ok()?)
+        })
+    }
+}
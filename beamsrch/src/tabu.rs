@@ -0,0 +1,55 @@
+use alloc::collections::BTreeMap;
+
+/// Treatment of tabu successors in [`crate::Beam::cycle`], see [`crate::Beam::with_tabu`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tabu {
+    /// Skip tabu successors unless a slot has nothing else to pick
+    Filter,
+    /// Add penalty to score of tabu successors
+    Penalty(u64),
+}
+
+/// Attributes of recently picked successors, shared by all slots
+#[derive(Debug)]
+pub(crate) struct TabuList<T> {
+    attribute: fn(&T) -> Option<u64>,
+    tenure: usize,
+    rule: Tabu,
+    /// Attribute to the last cycle it was picked in
+    recent: BTreeMap<u64, usize>,
+}
+
+impl<T> TabuList<T> {
+    pub(crate) fn new(tenure: usize, rule: Tabu, attribute: fn(&T) -> Option<u64>) -> Self {
+        Self {
+            attribute,
+            tenure,
+            rule,
+            recent: BTreeMap::new(),
+        }
+    }
+
+    /// Selection key of `node` scored `k` in cycle `depth`, lower is better
+    #[inline]
+    pub(crate) fn key(&self, node: &T, k: u64, depth: usize) -> (bool, u64) {
+        let tabu = (self.attribute)(node)
+            .and_then(|a| self.recent.get(&a))
+            .is_some_and(|&c| depth - c <= self.tenure);
+
+        match self.rule {
+            Tabu::Filter => (tabu, k),
+            Tabu::Penalty(p) => (false, k.saturating_add(p * tabu as u64)),
+        }
+    }
+
+    /// Remember `nodes` picked in cycle `depth`, forget attributes expiring after it
+    pub(crate) fn record<'a, I: Iterator<Item = &'a T>>(&mut self, nodes: I, depth: usize)
+    where
+        T: 'a,
+    {
+        self.recent.retain(|_, c| depth - *c < self.tenure);
+        nodes.filter_map(|n| (self.attribute)(n)).for_each(|a| {
+            self.recent.insert(a, depth);
+        });
+    }
+}
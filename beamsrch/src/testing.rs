@@ -0,0 +1,186 @@
+//! Conformance checks for [`Node`] implementations
+
+use alloc::boxed::Box;
+use core::mem::MaybeUninit;
+#[cfg(feature = "rayon")]
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use crate::{BeamError, Context, Inflate, Node};
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Violation {
+    /// [`Node::expand`] reported more successors than buffer slots it has taken
+    Overreported { reported: usize, taken: usize },
+    /// [`Node::expand`] count differs from [`Node::estimate`] for a buffer of estimated length
+    Estimate { estimated: usize, expanded: usize },
+    /// [`Node::estimate`] reported no successors, `Beam::from` has no slots to lay out
+    EmptyEstimate,
+    /// [`Node::evaluate`] scored the same node differently
+    Evaluate { first: u64, second: u64 },
+    /// [`Inflate::inflate`] changed [`Node::has_fulfilled`] status
+    Inflate { before: bool, after: bool },
+}
+
+/// Expansion count paired with buffer slots taken from iterator
+type Expansion = (Result<usize, BeamError>, usize);
+
+#[cfg(not(feature = "rayon"))]
+fn expand<const B: usize, T: Node<B>>(
+    node: &T,
+    ctx: &Context,
+    buf: &mut [MaybeUninit<T>],
+) -> Expansion {
+    let mut taken = 0;
+    let count = node.expand(ctx, buf.iter_mut().inspect(|_| taken += 1));
+    (count, taken)
+}
+
+#[cfg(feature = "rayon")]
+fn expand<const B: usize, T: Node<B> + Send>(
+    node: &T,
+    ctx: &Context,
+    buf: &mut [MaybeUninit<T>],
+) -> Expansion {
+    let taken = AtomicUsize::new(0);
+    let count = node.expand(
+        ctx,
+        buf.par_iter_mut().inspect(|_| {
+            taken.fetch_add(1, Ordering::Relaxed);
+        }),
+    );
+    (count, taken.into_inner())
+}
+
+fn evaluate<const B: usize, T: Node<B>>(node: &T, ctx: &Context) -> Result<u64, Violation> {
+    let first = node.evaluate(ctx);
+    let second = node.evaluate(ctx);
+    core::hint::select_unpredictable(
+        first == second,
+        Ok(first),
+        Err(Violation::Evaluate { first, second }),
+    )
+}
+
+/// Check `node` successors in `ctx` and take the best one, `None` if it has none
+fn step<const B: usize, T, F>(
+    node: &T,
+    ctx: &Context,
+    cap: usize,
+    expand: &F,
+) -> Result<Option<T>, Violation>
+where
+    T: Node<B>,
+    F: Fn(&T, &Context, &mut [MaybeUninit<T>]) -> Expansion,
+{
+    if node.estimate() == Some(0) {
+        return Err(Violation::EmptyEstimate);
+    }
+
+    let mut buf = Box::new_uninit_slice(cap);
+    let (Ok(count), taken) = expand(node, ctx, &mut buf) else {
+        return Ok(None);
+    };
+    if count > taken {
+        return Err(Violation::Overreported {
+            reported: count,
+            taken,
+        });
+    }
+
+    let children = buf
+        .iter_mut()
+        .take(count)
+        .map(|x| unsafe { x.assume_init_read() })
+        .collect::<Box<_>>();
+    let scores = children
+        .iter()
+        .enumerate()
+        .map(|(i, n)| evaluate(n, &ctx.child().at(i)))
+        .collect::<Result<Box<_>, _>>()?;
+
+    if let Some(estimated) = node.estimate() {
+        // `Beam::from` lays out a slot per estimated successor
+        let mut buf = Box::new_uninit_slice(estimated);
+        let (count, taken) = expand(node, ctx, &mut buf);
+        let expanded = count.unwrap_or(0);
+        if expanded > taken {
+            return Err(Violation::Overreported {
+                reported: expanded,
+                taken,
+            });
+        }
+        buf.iter_mut()
+            .take(expanded)
+            .for_each(|x| unsafe { x.assume_init_drop() });
+
+        if expanded != estimated {
+            return Err(Violation::Estimate {
+                estimated,
+                expanded,
+            });
+        }
+    }
+
+    let best = scores
+        .iter()
+        .enumerate()
+        .min_by_key(|&(_, k)| k)
+        .map(|(i, _)| i);
+    Ok(best.and_then(|i| children.into_vec().into_iter().nth(i)))
+}
+
+fn walk<const B: usize, T, F>(root: T, cap: usize, depth: usize, expand: F) -> Result<T, Violation>
+where
+    T: Node<B>,
+    F: Fn(&T, &Context, &mut [MaybeUninit<T>]) -> Expansion,
+{
+    let mut node = root;
+    for d in 0..depth {
+        match step(&node, &Context::new(d, d, 0), cap, &expand)? {
+            Some(next) => node = next,
+            None => break,
+        }
+    }
+    Ok(node)
+}
+
+/// Check [`Node`] invariants along the greedy path from `root`
+///
+/// Every node up to `depth` levels deep is expanded into a buffer of `cap` slots and
+/// replaced by its best scored successor; the deepest reached node is returned
+#[cfg(not(feature = "rayon"))]
+pub fn check<const B: usize, T: Node<B>>(
+    root: T,
+    cap: usize,
+    depth: usize,
+) -> Result<T, Violation> {
+    walk(root, cap, depth, expand)
+}
+
+/// Check [`Node`] invariants along the greedy path from `root`
+///
+/// Every node up to `depth` levels deep is expanded into a buffer of `cap` slots and
+/// replaced by its best scored successor; the deepest reached node is returned
+#[cfg(feature = "rayon")]
+pub fn check<const B: usize, T: Node<B> + Send>(
+    root: T,
+    cap: usize,
+    depth: usize,
+) -> Result<T, Violation> {
+    walk(root, cap, depth, expand)
+}
+
+/// Check that [`Inflate::inflate`] preserves [`Node::has_fulfilled`] status of `node`
+pub fn check_inflate<const B: usize, T: Inflate<B>>(mut node: T) -> Result<T, Violation> {
+    let before = node.has_fulfilled();
+    node.inflate();
+    let after = node.has_fulfilled();
+    core::hint::select_unpredictable(
+        before == after,
+        Ok(node),
+        Err(Violation::Inflate { before, after }),
+    )
+}
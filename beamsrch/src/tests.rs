@@ -1,9 +1,15 @@
 mod mock;
 
+use alloc::{vec, vec::Vec};
+
 #[cfg(feature = "rayon")]
 use rayon::prelude::*;
 
-use crate::{Beam, Node};
+use crate::{AStar, Beam, BeamError, Heuristic, Inflate, KBest, Lds, Node, Tabu, testing};
+#[cfg(feature = "std")]
+use crate::{Anneal, Budget, Mcts, Neighbourhood, Restart, Rng, Rollout, Trace, tune};
+#[cfg(feature = "memmap2")]
+use crate::{DiskBeam, Encode};
 
 #[test]
 fn simple_cycle() {
@@ -32,7 +38,10 @@ fn simple_cycle() {
 
     assert_eq!(
         beam.nodes()
-            .min_by_key(|node| <mock::MockNode<TH> as Node<BB>>::evaluate(*node))
+            .min_by_key(|node| <mock::MockNode<TH> as Node<BB>>::evaluate(
+                *node,
+                &Default::default()
+            ))
             .unwrap()
             .count,
         TH
@@ -66,9 +75,884 @@ fn varied_cycle() {
 
     assert_eq!(
         beam.nodes()
-            .min_by_key(|node| <mock::MockNode<TH> as Node<BB>>::evaluate(*node))
+            .min_by_key(|node| <mock::MockNode<TH> as Node<BB>>::evaluate(
+                *node,
+                &Default::default()
+            ))
             .unwrap()
             .count,
         TH
     );
 }
+
+#[test]
+fn scheduled_cycle() {
+    const BW: usize = 75;
+    const BB: usize = 75;
+    const TH: usize = 1_425;
+
+    let node = mock::MockNode::<TH>::default();
+    let mut beam: Beam<BW, BB, _> =
+        Beam::from(node).with_schedule(|depth| [BW, 10, 1, 40].get(depth).copied().unwrap_or(BW));
+
+    assert_eq!(beam.width(), BW);
+
+    let mut width = vec![];
+    while !beam.has_fulfilled() {
+        beam.cycle().unwrap();
+        width.push(beam.width());
+    }
+
+    assert_eq!(width[..5], [BW, 10, 1, 40, BW]);
+    assert_eq!(beam.depth(), width.len());
+    assert_eq!(
+        beam.nodes()
+            .min_by_key(|node| <mock::MockNode<TH> as Node<BB>>::evaluate(
+                *node,
+                &Default::default()
+            ))
+            .unwrap()
+            .count,
+        TH
+    );
+}
+
+#[test]
+fn node_conformance() {
+    const BB: usize = 75;
+    const TH: usize = 1_425;
+
+    let node = mock::MockNode::<TH>::default();
+    let leaf = testing::check::<BB, _>(node, BB, TH).unwrap();
+
+    assert!(<mock::MockNode<TH> as Node<BB>>::has_fulfilled(&leaf));
+}
+
+#[test]
+fn fallible_root() {
+    const BW: usize = 75;
+    const BB: usize = 75;
+    const TH: usize = 1_425;
+
+    let node = mock::MockNode::<TH> { count: TH };
+    assert_eq!(
+        Beam::<BW, BB, _>::try_from_root(node).unwrap_err(),
+        BeamError::Fulfilled
+    );
+
+    let node = mock::MockNode::<TH>::default();
+    let beam = Beam::<BW, BB, _>::try_from_root(node).unwrap();
+    assert_eq!(beam.width(), BW);
+}
+
+#[test]
+fn multiple_roots() {
+    const BW: usize = 75;
+    const BB: usize = 75;
+    const TH: usize = 1_425;
+
+    let roots = [0, 100, 200, TH].map(|count| mock::MockNode::<TH> { count });
+    let mut beam = Beam::<BW, BB, _>::from_roots(roots).unwrap();
+
+    assert_eq!(beam.width(), BW);
+    assert_eq!(
+        (0..3)
+            .map(|r| {
+                beam.node_buf
+                    .chunks_exact(BW + 1)
+                    .take(beam.width())
+                    .map(|c| unsafe { c[0].assume_init_ref().count })
+                    .filter(|&count| (r * 100..r * 100 + 100).contains(&count))
+                    .count()
+            })
+            .collect::<Vec<_>>(),
+        [25, 25, 25]
+    );
+
+    while !beam.has_fulfilled() {
+        beam.cycle().unwrap()
+    }
+
+    let roots = [0, 100, 200].map(|count| mock::MockNode::<TH> { count });
+    let beam = Beam::<2, BB, _>::from_roots(roots).unwrap();
+
+    assert_eq!(beam.width(), 2);
+    assert_eq!(unsafe { beam.node_buf[3].assume_init_ref().count }, 100);
+
+    let roots = [TH, TH + 1].map(|count| mock::MockNode::<TH> { count });
+    assert_eq!(
+        Beam::<BW, BB, _>::from_roots(roots).unwrap_err(),
+        BeamError::Fulfilled
+    );
+    assert_eq!(
+        Beam::<BW, BB, _>::from_roots([] as [mock::MockNode<TH>; 0]).unwrap_err(),
+        BeamError::NoSuccessors
+    );
+}
+
+/// Node counting up, preferring low counts in the first cycle and high ones later
+#[derive(Debug)]
+struct Aged(u64);
+
+impl<const B: usize> Node<B> for Aged {
+    #[cfg(not(feature = "rayon"))]
+    fn expand<'a, I: Iterator<Item = &'a mut core::mem::MaybeUninit<Self>>>(
+        &'a self,
+        _: &crate::Context,
+        iter: I,
+    ) -> Result<usize, BeamError> {
+        Ok((self.0 + 1..)
+            .zip(iter)
+            .map(|(k, x)| x.write(Self(k)))
+            .count())
+    }
+
+    #[cfg(feature = "rayon")]
+    fn expand<'a, I: IndexedParallelIterator<Item = &'a mut core::mem::MaybeUninit<Self>>>(
+        &'a self,
+        _: &crate::Context,
+        iter: I,
+    ) -> Result<usize, BeamError> {
+        Ok(iter
+            .enumerate()
+            .map(|(i, x)| x.write(Self(self.0 + i as u64 + 1)))
+            .count())
+    }
+
+    fn evaluate(&self, ctx: &crate::Context) -> u64 {
+        match ctx.depth {
+            0 => self.0,
+            _ => !self.0,
+        }
+    }
+
+    fn has_fulfilled(&self) -> bool {
+        false
+    }
+}
+
+#[test]
+fn roots_context() {
+    // successors of roots are ranked as heads of the first cycle
+    let beam = Beam::<3, 3, _>::from_roots([Aged(0), Aged(10)]).unwrap();
+    let mut heads = beam.frontier().map(|(.., n)| n.0).collect::<Vec<_>>();
+    heads.sort_unstable();
+    assert_eq!(heads, [1, 2, 11]);
+}
+
+#[test]
+fn frontier_access() {
+    const BW: usize = 75;
+    const BB: usize = 75;
+    const TH: usize = 1_425;
+
+    let node = mock::MockNode::<TH>::default();
+    let mut beam: Beam<BW, BB, _> = node.into();
+    beam.cycle().unwrap();
+
+    assert_eq!(beam.frontier().count(), BW);
+    assert_eq!(beam.nodes().count(), 0);
+    assert!(beam.frontier().all(|(i, k, node)| {
+        node.count == i + 1 + BW
+            && k == <mock::MockNode<TH> as Node<BB>>::evaluate(node, &beam.context(i))
+    }));
+
+    assert_eq!(beam.take_best().unwrap().count, BW * 2);
+    assert_eq!(beam.take_best().unwrap().count, BW * 2 - 1);
+    assert_eq!(beam.width(), BW - 2);
+}
+
+#[test]
+fn tabu_cycle() {
+    const BB: usize = 4;
+    const TH: usize = 1_000;
+
+    let run = |tabu: Option<Tabu>| {
+        let node = mock::MockNode::<TH>::default();
+        let mut beam = Beam::<BB, BB, _>::from(node).with_schedule(|_| 1);
+        if let Some(rule) = tabu {
+            beam = beam.with_tabu(1, rule, |n| Some(n.count as u64 % 4));
+        }
+        (0..3)
+            .map(|_| {
+                beam.cycle().unwrap();
+                unsafe { beam.node_buf[0].assume_init_ref().count }
+            })
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(run(None), [8, 12, 16]);
+    assert_eq!(run(Some(Tabu::Filter)), [8, 11, 14]);
+    assert_eq!(run(Some(Tabu::Penalty(2))), [8, 11, 14]);
+}
+
+#[test]
+fn adaptive_branching() {
+    const BB: usize = 16;
+    const TH: usize = 1_000;
+
+    let node = mock::MockNode::<TH>::default();
+    let mut beam = Beam::<BB, BB, _>::from(node).with_branching(64);
+    assert_eq!(beam.branching(), Some(&[][..]));
+
+    // successors score close together, limits widen to the buffer and scale to budget
+    let heads = |beam: &Beam<BB, BB, mock::MockNode<TH>>| {
+        beam.frontier().map(|(.., n)| n.count).collect::<Vec<_>>()
+    };
+    let before = heads(&beam);
+    beam.cycle().unwrap();
+    let after = heads(&beam);
+    assert_eq!(beam.branching(), Some(&[4; BB][..]));
+    assert!(before.iter().zip(&after).all(|(a, b)| b - a == BB));
+
+    let before = after;
+    beam.cycle().unwrap();
+    let after = heads(&beam);
+    assert!(before.iter().zip(&after).all(|(a, b)| b - a == 4));
+
+    // reordered slots are seeded again
+    let mut beam = beam.with_schedule(|_| 2);
+    beam.cycle().unwrap();
+    assert_eq!(beam.branching(), Some(&[16, 16][..]));
+}
+
+#[test]
+fn barren_slot() {
+    // successors past `usize::MAX` are not written, `expand` yields `Ok(0)` there
+    let node = mock::MockNode::<1_000> {
+        count: usize::MAX - 4,
+    };
+    let mut beam = Beam::<2, 2, _>::from(node);
+
+    beam.cycle().unwrap();
+    beam.cycle().unwrap();
+    assert_eq!(beam.cycle(), Err(BeamError::Exhausted));
+    assert!(!beam.is_poisoned());
+    assert!(beam.frontier().all(|(.., n)| n.count == usize::MAX));
+}
+
+/// Node without legal moves that says so through [`Node::estimate`]
+#[derive(Debug)]
+struct Barren;
+
+impl<const B: usize> Node<B> for Barren {
+    #[cfg(not(feature = "rayon"))]
+    fn expand<'a, I: Iterator<Item = &'a mut core::mem::MaybeUninit<Self>>>(
+        &'a self,
+        _: &crate::Context,
+        _: I,
+    ) -> Result<usize, BeamError> {
+        Ok(0)
+    }
+
+    #[cfg(feature = "rayon")]
+    fn expand<'a, I: IndexedParallelIterator<Item = &'a mut core::mem::MaybeUninit<Self>>>(
+        &'a self,
+        _: &crate::Context,
+        _: I,
+    ) -> Result<usize, BeamError> {
+        Ok(0)
+    }
+
+    fn evaluate(&self, _: &crate::Context) -> u64 {
+        0
+    }
+
+    fn has_fulfilled(&self) -> bool {
+        false
+    }
+
+    fn estimate(&self) -> Option<usize> {
+        Some(0)
+    }
+}
+
+#[test]
+fn barren_root() {
+    assert_eq!(
+        Beam::<4, 4, _>::try_from_root(Barren).unwrap_err(),
+        BeamError::NoSuccessors
+    );
+    assert_eq!(
+        testing::check::<4, _>(Barren, 4, 1).unwrap_err(),
+        testing::Violation::EmptyEstimate
+    );
+}
+
+#[test]
+fn k_best_harvest() {
+    const BW: usize = 75;
+    const BB: usize = 75;
+    const TH: usize = 1_425;
+
+    let node = mock::MockNode::<TH>::default();
+    let mut beam: Beam<BW, BB, _> = node.into();
+
+    let mut equal = KBest::new(3);
+    let mut bucket = KBest::with_signature(3, |n: &mock::MockNode<TH>| n.count as u64 / 10);
+    for _ in 0..TH / BW {
+        beam.cycle().unwrap();
+        beam.harvest(&mut equal);
+        beam.harvest(&mut bucket);
+    }
+
+    assert_eq!(
+        equal.into_vec().iter().map(|n| n.count).collect::<Vec<_>>(),
+        [1500, 1499, 1498]
+    );
+    assert_eq!(
+        bucket
+            .into_vec()
+            .iter()
+            .map(|n| n.count)
+            .collect::<Vec<_>>(),
+        [1500, 1499, 1489]
+    );
+}
+
+impl<const TH: usize, const B: usize> Inflate<B> for mock::MockNode<TH> {
+    fn inflate(&mut self) {
+        self.count = self.count.min(TH.saturating_sub(1));
+    }
+}
+
+#[test]
+fn inflated_beam() {
+    const BW: usize = 75;
+    const BB: usize = 75;
+    const TH: usize = 1_425;
+
+    let leaf = mock::MockNode::<TH> { count: TH };
+    assert_eq!(
+        testing::check_inflate::<BB, _>(leaf).unwrap_err(),
+        testing::Violation::Inflate {
+            before: true,
+            after: false
+        }
+    );
+
+    let node = mock::MockNode::<TH>::default();
+    let mut beam: Beam<BW, BB, _> = node.into();
+    beam.cycle().unwrap();
+    beam.extend();
+
+    assert_eq!((beam.width(), beam.depth()), (BW, 1));
+    assert!(beam.frontier().all(|(i, _, node)| node.count == i + 1 + BW));
+}
+
+impl<const TH: usize, const B: usize> Heuristic<B> for mock::MockNode<TH> {
+    fn cost(&self) -> u64 {
+        0
+    }
+
+    fn heuristic(&self) -> u64 {
+        TH.saturating_sub(self.count) as u64
+    }
+}
+
+#[test]
+fn best_first_search() {
+    const BB: usize = 75;
+    const TH: usize = 1_425;
+
+    let node = mock::MockNode::<TH>::default();
+    let mut search = AStar::<BB, _>::new(node, 1_000);
+
+    assert_eq!(search.run().unwrap().count, TH);
+    assert_eq!(search.expanded(), TH / BB);
+    assert!(search.open() <= 1_000);
+
+    let node = mock::MockNode::<TH>::default();
+    let mut search = AStar::<BB, _>::weighted(node, 1, 2.0);
+
+    assert_eq!(search.run().unwrap().count, TH);
+    assert_eq!(search.open(), 0);
+    assert_eq!(search.step().unwrap_err(), BeamError::Exhausted);
+}
+
+/// Node counting up to `GOAL`, heuristic offset far past `f32` precision
+#[derive(Debug, PartialEq)]
+struct Far<const GOAL: u64>(u64);
+
+impl<const GOAL: u64, const B: usize> Node<B> for Far<GOAL> {
+    #[cfg(not(feature = "rayon"))]
+    fn expand<'a, I: Iterator<Item = &'a mut core::mem::MaybeUninit<Self>>>(
+        &'a self,
+        _: &crate::Context,
+        iter: I,
+    ) -> Result<usize, BeamError> {
+        Ok((self.0 + 1..)
+            .zip(iter)
+            .map(|(k, x)| x.write(Self(k)))
+            .count())
+    }
+
+    #[cfg(feature = "rayon")]
+    fn expand<'a, I: IndexedParallelIterator<Item = &'a mut core::mem::MaybeUninit<Self>>>(
+        &'a self,
+        _: &crate::Context,
+        iter: I,
+    ) -> Result<usize, BeamError> {
+        Ok(iter
+            .enumerate()
+            .map(|(i, x)| x.write(Self(self.0 + i as u64 + 1)))
+            .count())
+    }
+
+    fn evaluate(&self, _: &crate::Context) -> u64 {
+        GOAL.saturating_sub(self.0)
+    }
+
+    fn has_fulfilled(&self) -> bool {
+        self.0 >= GOAL
+    }
+}
+
+impl<const GOAL: u64, const B: usize> Heuristic<B> for Far<GOAL> {
+    fn cost(&self) -> u64 {
+        0
+    }
+
+    fn heuristic(&self) -> u64 {
+        (1 << 40) + GOAL.saturating_sub(self.0)
+    }
+}
+
+#[test]
+fn weighted_search_precision() {
+    const BB: usize = 75;
+    const GOAL: u64 = BB as u64 * 2;
+
+    // heuristics differing far below `f32` precision still order successors
+    let mut search = AStar::<BB, _>::weighted(Far::<GOAL>(0), 1, 1.0);
+
+    assert_eq!(search.step().unwrap(), None);
+    assert_eq!(search.step().unwrap(), None);
+    assert_eq!(search.step().unwrap(), Some(Far(GOAL)));
+}
+
+#[test]
+fn discrepancy_search() {
+    const BB: usize = 75;
+    const TH: usize = 1_425;
+
+    let node = mock::MockNode::<TH>::default();
+    let mut search = Lds::<BB, _>::new(node, 3);
+
+    assert_eq!(search.probe(0).unwrap().count, TH);
+    assert_eq!(search.expanded(), TH / BB);
+    assert_eq!(search.run(2).unwrap().count, TH);
+
+    let node = mock::MockNode::<TH> { count: TH };
+    let mut search = Lds::<BB, _>::new(node, 3);
+
+    assert_eq!(search.run(2).unwrap_err(), BeamError::Fulfilled);
+}
+
+#[cfg(feature = "std")]
+impl<const TH: usize, const B: usize> Neighbourhood<B> for mock::MockNode<TH> {
+    fn perturb(&self, rng: &mut Rng) -> Option<Self> {
+        (self.count + rng.below(21))
+            .checked_sub(10)
+            .map(|count| Self { count })
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn local_search() {
+    const BB: usize = 75;
+    const TH: usize = 1_425;
+    const IT: usize = 1_000;
+
+    let node = mock::MockNode::<TH> { count: TH };
+    let mut search = Anneal::hill_climb(7);
+    let best = search.run::<BB, _>(node, Budget::new(IT));
+    let stats = search.stats();
+
+    assert!(best.count > TH);
+    assert_eq!(stats.iterations, IT);
+    assert_eq!(stats.accepted + stats.rejected + stats.infeasible, IT);
+    assert!(stats.improved <= stats.accepted);
+
+    let run = |seed| {
+        let mut search = Anneal::new(5.0, 0.99, seed);
+        let best = search.run::<BB, _>(node, Budget::new(IT));
+        (best, search.stats())
+    };
+    assert_eq!(run(7), run(7));
+    assert!(run(7).0.count > TH);
+
+    let mut search = Anneal::hill_climb(7);
+    let best = search.run::<BB, _>(node, Budget::new(IT).with_time(Default::default()));
+    assert_eq!((best, search.stats().iterations), (node, 0));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn tree_search() {
+    const BB: usize = 8;
+    const TH: usize = 100;
+    const IT: usize = 200;
+
+    for rollout in [Rollout::Random, Rollout::Greedy] {
+        let node = mock::MockNode::<TH>::default();
+        let mut search = Mcts::<BB, _>::new(node, 2f64.sqrt(), rollout, 7);
+
+        assert!(search.run(Budget::new(IT)).unwrap().count >= TH);
+        assert_eq!(search.iterations(), IT);
+        assert!(search.size() > IT);
+    }
+
+    let node = mock::MockNode::<TH> { count: TH };
+    let mut search = Mcts::<BB, _>::new(node, 2f64.sqrt(), Rollout::Random, 7);
+
+    assert_eq!(search.run(Budget::new(IT)).unwrap().count, TH);
+    assert_eq!(search.iterations(), 1);
+    assert_eq!(search.step().unwrap_err(), BeamError::Exhausted);
+}
+
+#[test]
+fn search_context() {
+    const BW: usize = 75;
+    const BB: usize = 75;
+    const TH: usize = 1_425;
+
+    let node = mock::MockNode::<TH>::default();
+    let mut beam: Beam<BW, BB, _> = Beam::from(node).with_seed(7);
+    beam.cycle().unwrap();
+    beam.cycle().unwrap();
+
+    let ctx = beam.context(3);
+    assert_eq!((ctx.depth, ctx.cycle, ctx.slot), (2, 2, 3));
+    assert_eq!(ctx.child().depth, 3);
+    assert_eq!(ctx.rng().next_u64(), beam.context(3).rng().next_u64());
+    assert_ne!(ctx.rng().next_u64(), ctx.at(4).rng().next_u64());
+    assert_ne!(ctx.rng().next_u64(), ctx.with_seed(8).rng().next_u64());
+}
+
+/// Live [`Fragile`] nodes count
+#[cfg(feature = "std")]
+static FRAGILE: core::sync::atomic::AtomicIsize = core::sync::atomic::AtomicIsize::new(0);
+
+/// Counts live instances, evaluation panics in slot 1 at depth `THRESHOLD`
+#[cfg(feature = "std")]
+#[derive(Debug)]
+struct Fragile<const THRESHOLD: usize>(usize);
+
+#[cfg(feature = "std")]
+impl<const THRESHOLD: usize> Fragile<THRESHOLD> {
+    fn new(count: usize) -> Self {
+        FRAGILE.fetch_add(1, core::sync::atomic::Ordering::Relaxed);
+        Self(count)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<const THRESHOLD: usize> Drop for Fragile<THRESHOLD> {
+    fn drop(&mut self) {
+        FRAGILE.fetch_sub(1, core::sync::atomic::Ordering::Relaxed);
+    }
+}
+
+#[cfg(feature = "std")]
+impl<const THRESHOLD: usize, const B: usize> Node<B> for Fragile<THRESHOLD> {
+    #[cfg(not(feature = "rayon"))]
+    fn expand<'a, I: Iterator<Item = &'a mut core::mem::MaybeUninit<Self>>>(
+        &'a self,
+        _: &crate::Context,
+        iter: I,
+    ) -> Result<usize, BeamError> {
+        Ok(iter
+            .enumerate()
+            .map(|(i, x)| x.write(Self::new(self.0 + i + 1)))
+            .count())
+    }
+
+    #[cfg(feature = "rayon")]
+    fn expand<'a, I: IndexedParallelIterator<Item = &'a mut core::mem::MaybeUninit<Self>>>(
+        &'a self,
+        _: &crate::Context,
+        iter: I,
+    ) -> Result<usize, BeamError> {
+        Ok(iter
+            .enumerate()
+            .map(|(i, x)| x.write(Self::new(self.0 + i + 1)))
+            .count())
+    }
+
+    fn evaluate(&self, ctx: &crate::Context) -> u64 {
+        assert!(
+            (ctx.slot, ctx.depth) != (1, THRESHOLD),
+            "fragile node evaluated"
+        );
+        !(self.0 as u64)
+    }
+
+    fn has_fulfilled(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn poisoned_cycle() {
+    use std::panic::{AssertUnwindSafe, catch_unwind};
+
+    const BW: usize = 8;
+    const BB: usize = 8;
+    const TH: usize = 2;
+
+    let mut beam: Beam<BW, BB, _> = Fragile::<TH>::new(0).into();
+    beam.cycle().unwrap();
+    assert!(!beam.is_poisoned());
+
+    assert!(catch_unwind(AssertUnwindSafe(|| beam.cycle())).is_err());
+    assert!(beam.is_poisoned());
+    assert_eq!(beam.cycle().unwrap_err(), BeamError::Poisoned);
+    assert_eq!(beam.frontier().count(), BW);
+
+    drop(beam);
+    assert_eq!(FRAGILE.load(core::sync::atomic::Ordering::Relaxed), 0);
+
+    // runner-ups kept to seed new slots are dropped too
+    let mut beam = Beam::<BW, BB, _>::from(Fragile::<TH>::new(0)).with_schedule(|d| 2 << d);
+    beam.cycle().unwrap();
+    assert_eq!(beam.width(), 2);
+
+    assert!(catch_unwind(AssertUnwindSafe(|| beam.cycle())).is_err());
+    assert!(beam.is_poisoned());
+
+    drop(beam);
+    assert_eq!(FRAGILE.load(core::sync::atomic::Ordering::Relaxed), 0);
+}
+
+/// LEB128 varint, records grow as counts do
+#[cfg(feature = "memmap2")]
+impl<const TH: usize> Encode for mock::MockNode<TH> {
+    fn size(&self) -> usize {
+        (usize::BITS - (self.count | 1).leading_zeros()).div_ceil(7) as usize
+    }
+
+    fn encode(&self, buf: &mut [u8]) {
+        let last = buf.len() - 1;
+        buf.iter_mut().enumerate().for_each(|(i, b)| {
+            *b = (self.count >> (7 * i)) as u8 & 0x7f | if i < last { 0x80 } else { 0 };
+        });
+    }
+
+    fn decode(buf: &[u8]) -> Self {
+        let count = buf
+            .iter()
+            .enumerate()
+            .fold(0, |acc, (i, b)| acc | ((b & 0x7f) as usize) << (7 * i));
+        assert_eq!(buf.last().map(|b| b & 0x80), Some(0));
+        Self { count }
+    }
+}
+
+#[cfg(feature = "memmap2")]
+#[test]
+fn disk_cycle() {
+    const BB: usize = 75;
+    const TH: usize = 1_425;
+    const CAP: usize = 1_000;
+
+    let file = tempfile::tempfile().unwrap();
+    let node = mock::MockNode::<TH>::default();
+    let mut beam = DiskBeam::<BB, _>::from_roots(&file, [node], CAP, 64).unwrap();
+
+    beam.cycle().unwrap();
+    assert_eq!(beam.width(), BB);
+    assert_eq!(beam.get(0).unwrap().count, BB);
+    assert_eq!(beam.get(1).unwrap().count, BB - 1);
+
+    while !beam.has_fulfilled() {
+        beam.cycle().unwrap();
+    }
+    assert_eq!(beam.width(), CAP);
+    assert_eq!(beam.best().unwrap().count, TH);
+    // replaced 1 byte heads by 2 byte ones, compacted on the way
+    assert!(beam.iter().all(|n| n.count >= 128));
+    assert!(file.metadata().unwrap().len() <= 8 * 2 * CAP as u64);
+
+    let node = mock::MockNode::<TH> { count: TH };
+    let err = DiskBeam::<BB, _>::from_roots(&file, [node], CAP, 64).unwrap_err();
+    assert_eq!(err.downcast::<BeamError>().unwrap(), BeamError::Fulfilled);
+
+    let err = DiskBeam::<BB, mock::MockNode<TH>>::from_roots(&file, [], CAP, 64).unwrap_err();
+    assert_eq!(
+        err.downcast::<BeamError>().unwrap(),
+        BeamError::NoSuccessors
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn parameter_tuning() {
+    const BW: usize = 75;
+    const BB: usize = 75;
+    const TH: usize = 1_425;
+
+    let node = mock::MockNode::<TH>::default();
+    assert_eq!(tune::solve::<BW, BB, _>(node, 10), Some(!(TH as u64)));
+
+    let axes: [&[f64]; 2] = [&[1.0, 2.0, 3.0, 4.0, 5.0], &[0.0, 1.0]];
+    let score = |x: &u64, p: &[f64]| (p[1] == 0.0).then(|| (p[0] - 3.0).abs() as u64 * 10 + x);
+
+    let report = tune::search(
+        &axes,
+        tune::Strategy::Grid,
+        &[1, 3],
+        Budget::new(100),
+        score,
+    );
+    assert_eq!(report.trials.len(), 10);
+    assert_eq!(report.trials[1].params, [1.0, 1.0]);
+    assert_eq!(report.trials[1].mean, f64::INFINITY);
+    let best = report.best().unwrap();
+    assert_eq!((&best.params[..], best.mean), (&[3.0, 0.0][..], 2.0));
+
+    let report = tune::search(
+        &axes,
+        tune::Strategy::Random(7),
+        &[1, 3],
+        Budget::new(50),
+        score,
+    );
+    assert_eq!(report.trials.len(), 50);
+    assert_eq!(report.best().unwrap().params, [3.0, 0.0]);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn iterated_search() {
+    const BW: usize = 75;
+    const BB: usize = 75;
+    const TH: usize = 1_425;
+    const IT: usize = 20;
+
+    let ruin = |n: &mock::MockNode<TH>, rng: &mut Rng| {
+        let count = n.count - 1 - rng.below(BB);
+        Some(mock::MockNode::<TH> { count })
+    };
+    let run = |seed| {
+        let mut search = Restart::new(ruin, seed);
+        let node = mock::MockNode::<TH>::default();
+        let best = search.run::<BW, BB, _>(node, Budget::new(IT)).unwrap();
+        (best, search.stats())
+    };
+
+    let (best, stats) = run(7);
+    assert!(best.count > TH);
+    assert_eq!(stats.iterations, IT);
+    assert_eq!(stats.accepted + stats.rejected + stats.infeasible, IT);
+    assert!(stats.improved > 0);
+    assert_eq!(run(7), (best, stats));
+
+    let mut search = Restart::new(|_: &mock::MockNode<TH>, _: &mut Rng| None, 7);
+    let node = mock::MockNode::<TH>::default();
+    let best = search.run::<BW, BB, _>(node, Budget::new(IT)).unwrap();
+    assert_eq!((best.count, search.stats().infeasible), (TH, IT));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn traced_cycle() {
+    use std::io::{Read, Seek};
+
+    const BW: usize = 2;
+    const BB: usize = 2;
+    const TH: usize = 100;
+
+    let trace = |format| {
+        let mut file = tempfile::tempfile().unwrap();
+        let node = mock::MockNode::<TH>::default();
+        let mut beam =
+            Beam::<BW, BB, _>::from(node).with_trace(file.try_clone().unwrap(), format, |n, f| {
+                write!(f, "{}", n.count)
+            });
+        beam.cycle().unwrap();
+        beam.cycle().unwrap();
+        beam.finish_trace().unwrap();
+
+        let mut out = std::string::String::new();
+        file.rewind().unwrap();
+        file.read_to_string(&mut out).unwrap();
+        out
+    };
+
+    let out = trace(Trace::JsonLines);
+    let lines = out.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), BW * 2);
+    assert_eq!(
+        lines[0],
+        std::format!(
+            r#"{{"cycle":0,"slot":0,"parent":"1","children":[{{"label":"2","score":{}}},{{"label":"3","score":{}}}],"survivor":1,"seeded":[]}}"#,
+            !2u64,
+            !3u64
+        )
+    );
+    assert!(lines[3].starts_with(r#"{"cycle":1,"slot":1,"parent":"4","#));
+
+    let out = trace(Trace::Dot);
+    assert!(out.starts_with("digraph beam {\n  n0 [label=\"1\"];\n"));
+    assert!(out.contains("  n0 -> n2 [label=\"0\" style=bold];\n"));
+    assert!(out.contains("  n2 -> n6 [label=\"0\"];\n"));
+    assert!(out.ends_with("}\n"));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn traced_schedule() {
+    use std::io::{Read, Seek};
+
+    const BW: usize = 4;
+    const BB: usize = 4;
+    const TH: usize = 100;
+
+    let trace = |format| {
+        let mut file = tempfile::tempfile().unwrap();
+        let node = mock::MockNode::<TH>::default();
+        let mut beam = Beam::<BW, BB, _>::from(node)
+            .with_schedule(|d| [1, BW, BW, 2][d.min(3)])
+            .with_trace(file.try_clone().unwrap(), format, |n, f| {
+                write!(f, "{}", n.count)
+            });
+        (0..4).for_each(|_| beam.cycle().unwrap());
+        beam.finish_trace().unwrap();
+
+        let mut out = std::string::String::new();
+        file.rewind().unwrap();
+        file.read_to_string(&mut out).unwrap();
+        out
+    };
+
+    let out = trace(Trace::JsonLines);
+    let lines = out.lines().collect::<Vec<_>>();
+    assert_eq!(lines[0], r#"{"cycle":0,"slot":2,"dropped":"3"}"#);
+    assert!(lines[4].ends_with(
+        r#""survivor":3,"seeded":[{"child":2,"slot":1},{"child":1,"slot":2},{"child":0,"slot":3}]}"#
+    ));
+    assert!(lines[6].starts_with(r#"{"cycle":2,"slot":1,"parent":"11","#));
+    assert_eq!(
+        lines.iter().filter(|l| l.contains(r#""dropped":"#)).count(),
+        5
+    );
+
+    // every expanded head but the kept root is drawn from its parent
+    let out = trace(Trace::Dot);
+    let edges = out
+        .lines()
+        .filter_map(|l| l.trim().split_once(" -> "))
+        .map(|(a, b)| (a, b.split(' ').next().unwrap()))
+        .collect::<Vec<_>>();
+    let mut roots = edges
+        .iter()
+        .map(|&(a, _)| a)
+        .filter(|&a| edges.iter().all(|&(_, b)| a != b))
+        .collect::<Vec<_>>();
+    roots.dedup();
+    assert_eq!(roots, ["n3"]);
+    assert_eq!(out.matches("[style=dashed]").count(), 5);
+}
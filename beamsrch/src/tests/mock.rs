@@ -1,11 +1,11 @@
-use std::mem::MaybeUninit;
+use core::mem::MaybeUninit;
 
 #[cfg_attr(feature = "rayon", allow(unused_imports))]
 use itertools::Itertools;
 #[cfg(feature = "rayon")]
 use rayon::prelude::*;
 
-use crate::{BeamError, Node};
+use crate::{BeamError, Context, Node};
 
 #[derive(Debug, Default, Clone, Copy, PartialEq)]
 pub struct MockNode<const THRESHOLD: usize> {
@@ -16,6 +16,7 @@ impl<const THRESHOLD: usize, const B: usize> Node<B> for MockNode<THRESHOLD> {
     #[cfg(not(feature = "rayon"))]
     fn expand<'a, I: Iterator<Item = &'a mut MaybeUninit<Self>>>(
         &'a self,
+        _: &Context,
         iter: I,
     ) -> Result<usize, BeamError> {
         let fill_op = |(i, x): (usize, &mut MaybeUninit<Self>)| {
@@ -33,6 +34,7 @@ impl<const THRESHOLD: usize, const B: usize> Node<B> for MockNode<THRESHOLD> {
         I: ParallelIterator<Item = &'a mut MaybeUninit<Self>> + IndexedParallelIterator,
     >(
         &'a self,
+        _: &Context,
         iter: I,
     ) -> Result<usize, BeamError> {
         let fill_op = |(i, x): (usize, &mut MaybeUninit<Self>)| {
@@ -44,7 +46,7 @@ impl<const THRESHOLD: usize, const B: usize> Node<B> for MockNode<THRESHOLD> {
         Ok(iter.enumerate().map(fill_op).while_some().count())
     }
 
-    fn evaluate(&self) -> u64 {
+    fn evaluate(&self, _: &Context) -> u64 {
         !(self.count as u64)
     }
 
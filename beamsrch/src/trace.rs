@@ -0,0 +1,264 @@
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt;
+#[cfg(feature = "std")]
+use std::{
+    boxed::Box,
+    format,
+    io::{self, Write},
+};
+
+/// Node label hook formatting like [`fmt::Display::fmt`]
+pub type Label<T> = fn(&T, &mut fmt::Formatter<'_>) -> fmt::Result;
+
+struct Show<'a, T>(&'a T, Label<T>);
+
+impl<T> fmt::Display for Show<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        (self.1)(self.0, f)
+    }
+}
+
+#[inline]
+pub(crate) fn label<T>(node: &T, label: Label<T>) -> String {
+    Show(node, label).to_string()
+}
+
+/// Slot expansion recorded during a cycle
+#[cfg_attr(not(feature = "std"), allow(dead_code))]
+#[derive(Debug, Default)]
+pub(crate) struct Expansion {
+    pub(crate) parent: String,
+    /// `(label, score)` in expansion order
+    pub(crate) children: Vec<(String, u64)>,
+    pub(crate) survivor: Option<usize>,
+    /// `(child, slot)` runner-ups surviving as heads of new slots
+    pub(crate) seeded: Vec<(usize, usize)>,
+}
+
+/// Output format of [`crate::Beam::with_trace`]
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trace {
+    /// A JSON object per slot per cycle
+    JsonLines,
+    /// Graphviz digraph, survivors drawn bold
+    Dot,
+}
+
+/// Writer of cycle records, see [`crate::Beam::with_trace`]
+#[cfg(feature = "std")]
+pub(crate) struct Tracer<T> {
+    out: Box<dyn Write + Send + Sync>,
+    format: Trace,
+    pub(crate) label: Label<T>,
+    /// DOT node ids of slot heads, `None` if not emitted yet
+    heads: Vec<Option<usize>>,
+    /// DOT nodes emitted count
+    next: usize,
+    /// First write error, stops tracing
+    error: Option<io::Error>,
+    closed: bool,
+}
+
+#[cfg(feature = "std")]
+impl<T> fmt::Debug for Tracer<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Tracer")
+            .field("format", &self.format)
+            .field("next", &self.next)
+            .field("error", &self.error)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "std")]
+fn json(s: &str) -> String {
+    use fmt::Write;
+
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    s.chars().for_each(|c| match c {
+        '"' => out.push_str("\\\""),
+        '\\' => out.push_str("\\\\"),
+        '\n' => out.push_str("\\n"),
+        c if c < ' ' => {
+            let _ = write!(out, "\\u{:04x}", c as u32);
+        }
+        c => out.push(c),
+    });
+    out.push('"');
+    out
+}
+
+#[cfg(feature = "std")]
+fn dot(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(feature = "std")]
+impl<T> Tracer<T> {
+    pub(crate) fn new<W: Write + Send + Sync + 'static>(
+        out: W,
+        format: Trace,
+        label: Label<T>,
+    ) -> Self {
+        let mut tracer = Self {
+            out: Box::new(out),
+            format,
+            label,
+            heads: Vec::new(),
+            next: 0,
+            error: None,
+            closed: false,
+        };
+        if format == Trace::Dot {
+            tracer.write(|out| writeln!(out, "digraph beam {{"));
+        }
+        tracer
+    }
+
+    fn write<F: FnOnce(&mut dyn Write) -> io::Result<()>>(&mut self, f: F) {
+        if self.error.is_none() {
+            self.error = f(&mut self.out).err();
+        }
+    }
+
+    /// Carry DOT ids of slot heads over a reorder, slot `p` is now old slot `order[p]`
+    pub(crate) fn reorder(&mut self, order: &[usize]) {
+        self.heads = order
+            .iter()
+            .map(|&i| self.heads.get(i).copied().flatten())
+            .collect();
+    }
+
+    /// Write `(slot, label)` heads dropped before expansion in cycle `depth`
+    pub(crate) fn dropped(&mut self, depth: usize, dropped: Vec<(usize, String)>) {
+        dropped
+            .into_iter()
+            .for_each(|(slot, label)| match self.format {
+                Trace::JsonLines => {
+                    let label = json(&label);
+                    self.write(|out| {
+                        writeln!(
+                            out,
+                            r#"{{"cycle":{depth},"slot":{slot},"dropped":{label}}}"#
+                        )
+                    });
+                }
+                Trace::Dot => {
+                    let id = self.head(slot, &label);
+                    self.write(|out| writeln!(out, "  n{id} [style=dashed];"));
+                }
+            });
+    }
+
+    /// DOT id of `slot` head, emitted with `label` if not yet
+    fn head(&mut self, slot: usize, label: &str) -> usize {
+        if let Some(id) = self.heads.get(slot).copied().flatten() {
+            return id;
+        }
+
+        let id = self.next;
+        self.next += 1;
+        let label = dot(label);
+        self.write(|out| writeln!(out, "  n{id} [label=\"{label}\"];"));
+        if let Some(head) = self.heads.get_mut(slot) {
+            *head = Some(id);
+        }
+        id
+    }
+
+    /// Write expansions of cycle `depth` by slot
+    pub(crate) fn record(&mut self, depth: usize, traces: Vec<Option<Expansion>>) {
+        let seeded = traces
+            .iter()
+            .flatten()
+            .flat_map(|e| e.seeded.iter().map(|&(_, t)| t + 1))
+            .max()
+            .unwrap_or(0);
+        if self.heads.len() < traces.len().max(seeded) {
+            self.heads.resize(traces.len().max(seeded), None);
+        }
+
+        (0..)
+            .zip(traces)
+            .filter_map(|(s, e)| e.map(|e| (s, e)))
+            .for_each(|(s, e)| match self.format {
+                Trace::JsonLines => self.json(depth, s, e),
+                Trace::Dot => self.dot(s, e),
+            });
+    }
+
+    fn json(&mut self, depth: usize, slot: usize, e: Expansion) {
+        let children = e
+            .children
+            .iter()
+            .map(|(l, k)| format!(r#"{{"label":{},"score":{k}}}"#, json(l)))
+            .collect::<Vec<_>>()
+            .join(",");
+        let survivor = e.survivor.map_or("null".to_string(), |j| j.to_string());
+        let seeded = e
+            .seeded
+            .iter()
+            .map(|(j, t)| format!(r#"{{"child":{j},"slot":{t}}}"#))
+            .collect::<Vec<_>>()
+            .join(",");
+        let parent = json(&e.parent);
+
+        self.write(|out| {
+            writeln!(
+                out,
+                r#"{{"cycle":{depth},"slot":{slot},"parent":{parent},"children":[{children}],"survivor":{survivor},"seeded":[{seeded}]}}"#
+            )
+        });
+    }
+
+    fn dot(&mut self, slot: usize, e: Expansion) {
+        let parent = self.head(slot, &e.parent);
+
+        e.children.iter().enumerate().for_each(|(j, (l, k))| {
+            let id = self.next;
+            self.next += 1;
+            let label = dot(l);
+            let seeded = e.seeded.iter().find(|&&(c, _)| c == j).map(|&(_, t)| t);
+            let survives = (e.survivor == Some(j)) | seeded.is_some();
+            let style = if survives { " style=bold" } else { "" };
+            self.write(|out| {
+                writeln!(out, "  n{id} [label=\"{label}\\n{k}\"{style}];")?;
+                writeln!(out, "  n{parent} -> n{id} [label=\"{slot}\"{style}];")
+            });
+            if e.survivor == Some(j) {
+                self.heads[slot] = Some(id);
+            }
+            if let Some(t) = seeded {
+                self.heads[t] = Some(id);
+            }
+        });
+    }
+
+    /// Close the output, returns the first write error
+    pub(crate) fn finish(&mut self) -> io::Result<()> {
+        if self.closed {
+            return Ok(());
+        }
+        self.closed = true;
+
+        if self.format == Trace::Dot {
+            self.write(|out| writeln!(out, "}}"));
+        }
+        self.write(|out| out.flush());
+        self.error.take().map_or(Ok(()), Err)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> Drop for Tracer<T> {
+    fn drop(&mut self) {
+        let _ = self.finish();
+    }
+}
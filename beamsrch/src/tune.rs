@@ -0,0 +1,142 @@
+//! Parameter search for node heuristics over a set of instances
+
+use alloc::vec::Vec;
+use std::time::Instant;
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use crate::{Beam, Budget, Node, Rng};
+
+/// Order candidate parameter sets are drawn in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    /// Every combination of axis values once, last axis varying fastest
+    Grid,
+    /// Axis values drawn uniformly with `seed` until budget runs out
+    Random(u64),
+}
+
+/// Parameter set scored over all instances
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trial {
+    /// One value per axis
+    pub params: Vec<f64>,
+    /// Mean score, infinite if any instance is left unsolved
+    pub mean: f64,
+}
+
+/// Trials of a [`search`] in run order
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Report {
+    pub trials: Vec<Trial>,
+}
+
+impl Report {
+    /// Trial with the lowest finite mean score, earliest on ties
+    pub fn best(&self) -> Option<&Trial> {
+        self.trials
+            .iter()
+            .filter(|t| t.mean.is_finite())
+            .min_by(|a, b| a.mean.total_cmp(&b.mean))
+    }
+}
+
+/// Score parameter sets drawn from `axes` by `strategy` within `budget`
+///
+/// Every trial runs `score` on each of `instances`, `None` marks an unsolved instance.
+/// Budget time is checked between trials, so a trial in progress is never cut short
+pub fn search<I, F>(
+    axes: &[&[f64]],
+    strategy: Strategy,
+    instances: &[I],
+    budget: Budget,
+    mut score: F,
+) -> Report
+where
+    F: FnMut(&I, &[f64]) -> Option<u64>,
+{
+    let total = axes.iter().map(|a| a.len()).product::<usize>();
+    let (mut rng, count) = match strategy {
+        Strategy::Grid => (None, total),
+        Strategy::Random(seed) => (Some(Rng::new(seed)), usize::MAX * (total > 0) as usize),
+    };
+
+    let trials = budget
+        .iter(Instant::now())
+        .take(count)
+        .map(|i| {
+            let params = match &mut rng {
+                // mixed radix digits of `i`
+                None => {
+                    let mut i = i;
+                    let mut params = axes
+                        .iter()
+                        .rev()
+                        .map(|a| {
+                            let v = a[i % a.len()];
+                            i /= a.len();
+                            v
+                        })
+                        .collect::<Vec<_>>();
+                    params.reverse();
+                    params
+                }
+                Some(rng) => axes.iter().map(|a| a[rng.below(a.len())]).collect(),
+            };
+
+            let mean = instances
+                .iter()
+                .map(|x| score(x, &params).map_or(f64::INFINITY, |k| k as f64))
+                .sum::<f64>()
+                / instances.len().max(1) as f64;
+            Trial { params, mean }
+        })
+        .collect();
+
+    Report { trials }
+}
+
+/// Cycle a beam from `root` narrowed to `width` slots until it has fulfilled, returns
+/// the best fulfilled score
+///
+/// Width is applied through [`Beam::with_schedule`], `W` bounds it from above
+#[cfg(not(feature = "rayon"))]
+pub fn solve<const W: usize, const B: usize, T>(root: T, width: usize) -> Option<u64>
+where
+    T: Node<B>,
+{
+    let mut beam = Beam::<W, B, T>::try_from_root(root)
+        .ok()?
+        .with_schedule(move |_| width);
+    while !beam.has_fulfilled() {
+        beam.cycle().ok()?;
+    }
+
+    beam.frontier()
+        .filter(|(.., n)| n.has_fulfilled())
+        .map(|(_, k, _)| k)
+        .min()
+}
+
+/// Cycle a beam from `root` narrowed to `width` slots until it has fulfilled, returns
+/// the best fulfilled score
+///
+/// Width is applied through [`Beam::with_schedule`], `W` bounds it from above
+#[cfg(feature = "rayon")]
+pub fn solve<const W: usize, const B: usize, T>(root: T, width: usize) -> Option<u64>
+where
+    T: Node<B> + Send + Sync,
+{
+    let mut beam = Beam::<W, B, T>::try_from_root(root)
+        .ok()?
+        .with_schedule(move |_| width);
+    while !beam.has_fulfilled() {
+        beam.cycle().ok()?;
+    }
+
+    beam.frontier()
+        .filter(|(.., n)| n.has_fulfilled())
+        .map(|(_, k, _)| k)
+        .min()
+}
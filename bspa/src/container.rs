@@ -0,0 +1,216 @@
+use beamsrch::Beam;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use crate::{Area, BspaNode, Packing, Placement, Rect};
+
+/// Constraints on container size, see [`pack_container`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Container {
+    /// Width and height are powers of two
+    pub pow2: bool,
+    /// Width equals height
+    pub square: bool,
+    /// Largest `(w, h)` allowed
+    pub max: Option<(u32, u32)>,
+}
+
+impl Container {
+    /// Smallest size satisfying the constraints that covers `w` by `h`
+    pub fn fit(&self, w: u32, h: u32) -> Option<(u32, u32)> {
+        let (mut w, mut h) = match self.square {
+            true => (w.max(h), w.max(h)),
+            false => (w, h),
+        };
+        if self.pow2 {
+            w = w.checked_next_power_of_two()?;
+            h = h.checked_next_power_of_two()?;
+        }
+
+        match self.max {
+            Some((mw, mh)) if (w > mw) | (h > mh) => None,
+            _ => Some((w, h)),
+        }
+    }
+
+    /// Container widths worth packing `items` into as set by `options`, narrowest first
+    ///
+    /// Powers of two if `pow2` is set, widths growing by a quarter otherwise; from the
+    /// widest item up to `max` or the items side by side, margins included
+    fn widths(&self, items: &[Rect], options: &Packing) -> Vec<u32> {
+        let items = items.iter().map(|&r| options.item(r)).collect::<Vec<_>>();
+        let side = |r: &Rect| match r.is_rotatable() {
+            true => r.w().min(r.h()),
+            false => r.w(),
+        };
+        let outer = |w: u32| options.outer(w);
+        let lo = items.iter().map(side).max().map_or(1, outer).max(1);
+        let hi = self
+            .max
+            .map_or(outer(items.iter().map(|r| r.w()).sum()), |(w, _)| w);
+
+        let next = |w: &u32| match self.pow2 {
+            true => w.checked_mul(2),
+            false => Some(w + w.div_ceil(4)),
+        };
+        let start = match self.pow2 {
+            true => lo.checked_next_power_of_two(),
+            false => Some(lo),
+        };
+        let mut widths = core::iter::successors(start, next)
+            .take_while(|&w| w <= hi)
+            .collect::<Vec<_>>();
+        if !self.pow2 && widths.last().is_some_and(|&w| w < hi) {
+            widths.push(hi);
+        }
+        widths
+    }
+}
+
+/// Items packed by [`pack_container`]
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Packed {
+    pub w: u32,
+    pub h: u32,
+    /// Placement of every input item by input index
+    pub placements: Vec<Placement<Rect>>,
+}
+
+/// Pack `avai_box` into the smallest container satisfying `container`
+///
+/// Strips of every width from [`Container`] candidates are packed by a [`Beam`] as set
+/// by `options`, extended once exhausted; the used extent of each, border included, is
+/// fitted to the constraints and the least area wins, narrowest on ties. `None` if no
+/// candidate fits; `options.page` is left unused, heights follow from `container`
+pub fn pack_container<const W: usize, const B: usize, I>(
+    avai_box: I,
+    container: Container,
+    n: usize,
+    f: f32,
+    options: Packing,
+) -> Option<Packed>
+where
+    I: IntoIterator<Item = Rect>,
+{
+    let items = avai_box.into_iter().collect::<Vec<_>>();
+    let options = Packing {
+        page: None,
+        ..options
+    };
+
+    container
+        .widths(&items, &options)
+        .into_iter()
+        .filter_map(|width| {
+            let root = BspaNode::with_options(items.iter().copied(), width, n, f, options);
+            let mut beam = Beam::<W, B, _>::try_from_root(root).ok()?;
+            while !beam.has_fulfilled() && beam.cycle().is_ok() {}
+            if !beam.has_fulfilled() {
+                beam.extend();
+                while !beam.has_fulfilled() && beam.cycle().is_ok() {}
+            }
+
+            beam.frontier()
+                .filter_map(|(i, _, node)| {
+                    let placements = node.layout()?;
+                    // content already starts past the border, trailing spacing left out
+                    let w = placements.iter().map(|p| p.x + p.w()).max()?;
+                    let h = placements.iter().map(|p| p.y + p.h()).max()?;
+                    let (w, h) = (
+                        w.checked_add(options.border)?,
+                        h.checked_add(options.border)?,
+                    );
+                    let (w, h) = container.fit(w, h)?;
+                    Some((w as u64 * h as u64, i, Packed { w, h, placements }))
+                })
+                .min_by_key(|(s, i, _)| (*s, *i))
+                .map(|(s, _, packed)| (s, packed))
+        })
+        .min_by_key(|(s, packed)| (*s, packed.w))
+        .map(|(_, packed)| packed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RECT_S: Rect = Rect::new(8, 8);
+    const RECT_L: Rect = Rect::new(16, 16);
+
+    #[test]
+    fn fit() {
+        let pow2 = Container {
+            pow2: true,
+            ..Default::default()
+        };
+        let square = Container {
+            square: true,
+            max: Some((32, 32)),
+            ..Default::default()
+        };
+
+        assert_eq!(pow2.fit(24, 9), Some((32, 16)));
+        assert_eq!(pow2.fit(u32::MAX, 1), None);
+        assert_eq!(square.fit(24, 9), Some((24, 24)));
+        assert_eq!(square.fit(33, 9), None);
+        assert_eq!(
+            pow2.widths(&[RECT_S, RECT_L, RECT_S], &Packing::default()),
+            [16, 32]
+        );
+    }
+
+    #[test]
+    fn smallest_container() {
+        const BB: usize = 16;
+        const ITEMS: [Rect; 5] = [RECT_S, RECT_S, RECT_L, RECT_S, RECT_S];
+
+        let container = Container {
+            pow2: true,
+            ..Default::default()
+        };
+        let packed =
+            pack_container::<BB, BB, _>(ITEMS, container, 16, 1.0, Packing::default()).unwrap();
+        assert_eq!(packed.w * packed.h, 512);
+        assert!(packed.w.is_power_of_two() & packed.h.is_power_of_two());
+        assert!(ITEMS.iter().zip(&packed.placements).all(|(r, p)| {
+            (&p.item == r) & (p.x + p.w() <= packed.w) & (p.y + p.h() <= packed.h)
+        }));
+
+        let container = Container {
+            square: true,
+            max: Some((24, 24)),
+            ..Default::default()
+        };
+        let packed =
+            pack_container::<BB, BB, _>(ITEMS, container, 16, 1.0, Packing::default()).unwrap();
+        assert_eq!((packed.w, packed.h), (24, 24));
+    }
+
+    #[test]
+    fn margin_container() {
+        const BB: usize = 16;
+        const SPACING: u32 = 2;
+        const BORDER: u32 = 3;
+
+        let container = Container {
+            square: true,
+            ..Default::default()
+        };
+        let options = Packing {
+            spacing: SPACING,
+            border: BORDER,
+            ..Default::default()
+        };
+        let packed =
+            pack_container::<BB, BB, _>([RECT_S].repeat(4), container, 16, 1.0, options).unwrap();
+        // 2 by 2 items, one spacing apart and a border around
+        assert_eq!((packed.w, packed.h), (24, 24));
+        assert!(packed.placements.iter().all(|p| {
+            (p.x >= BORDER)
+                & (p.y >= BORDER)
+                & (p.x + p.w() <= packed.w - BORDER)
+                & (p.y + p.h() <= packed.h - BORDER)
+        }));
+        assert_eq!(container.widths(&[RECT_S], &options), [14]);
+    }
+}
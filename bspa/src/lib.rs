@@ -1,8 +1,10 @@
 mod area;
+mod container;
 mod node;
+mod page;
 mod types;
 
-use std::mem::MaybeUninit;
+use std::{mem::MaybeUninit, sync::Arc};
 
 use itertools::Itertools;
 #[cfg(feature = "rayon")]
@@ -10,6 +12,8 @@ use rayon::prelude::*;
 use rustc_hash::FxHashMap;
 
 pub use beamsrch::*;
+pub use container::*;
+pub use page::*;
 pub use types::*;
 
 pub trait Area {
@@ -26,6 +30,59 @@ pub trait Area {
     fn h(&self) -> u32;
 }
 
+/// Options of [`BspaNode::with_options`], [`pack_pages`] and [`pack_container`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Packing {
+    /// Fixed container height, items that don't fit are left out; a strip otherwise
+    pub page: Option<u32>,
+    /// Gap kept between items
+    pub spacing: u32,
+    /// Gap kept between items and the container edges
+    pub border: u32,
+    /// Every item may be placed rotated by 90°
+    pub rotation: bool,
+}
+
+impl Packing {
+    /// Width and page height packed into for a container of width `w`
+    ///
+    /// Items padded by `spacing` are packed into `side - 2 * border + spacing`; panics
+    /// if the margins leave no width or height to pack into
+    fn extent(&self, w: u32) -> (u32, Option<u32>) {
+        let Self {
+            spacing, border, ..
+        } = *self;
+        let inner = |side: u32, name: &str| {
+            border
+                .checked_mul(2)
+                .and_then(|b| side.checked_add(spacing)?.checked_sub(b))
+                .filter(|&side| side > 0)
+                .unwrap_or_else(|| {
+                    panic!(
+                        "margins of spacing {spacing} and border {border} leave no {name} of {side}"
+                    )
+                })
+        };
+        (inner(w, "width"), self.page.map(|h| inner(h, "height")))
+    }
+
+    /// Container side around a packing side `side`, the inverse of [`Packing::extent`]
+    #[inline]
+    fn outer(&self, side: u32) -> u32 {
+        side.saturating_add(self.border.saturating_mul(2))
+            .saturating_sub(self.spacing)
+    }
+
+    /// Item as packed, padded by `spacing` and rotatable if `rotation` is set
+    #[inline]
+    fn item(&self, item: Rect) -> Rect {
+        match self.rotation {
+            true => item.rotatable().padded(self.spacing),
+            false => item.padded(self.spacing),
+        }
+    }
+}
+
 #[derive(Debug, Default, Clone, PartialEq)]
 pub struct BspaNode {
     /// Spaces available to place blocks
@@ -36,27 +93,73 @@ pub struct BspaNode {
     avai_box: FxHashMap<Rect, usize>,
     /// Not yet placed available blocks from combined boxes
     avai_blk: Vec<RectGroup>,
+    /// Input items in input order, shared by all nodes of a run
+    items: Arc<[Rect]>,
+    /// Margin around packed blocks, see [`Packing::border`]
+    border: u32,
 }
 
 impl BspaNode {
+    /// Node packing `avai_box` into a strip of width `w`
+    ///
+    /// Up to `n` combined blocks are kept next to single item grids, all filled at least
+    /// by `f`; items marked [`Rect::rotatable`] may be placed rotated by 90°
     pub fn new<I: IntoIterator<Item = Rect>>(avai_box: I, w: u32, n: usize, f: f32) -> Self {
-        let mut avai_box = avai_box
-            .into_iter()
-            .fold(FxHashMap::default(), |mut acc, c| {
-                *acc.entry(c).or_default() += 1;
-                acc
-            });
+        Self::build(avai_box, w, None, n, f)
+    }
+
+    /// Like [`BspaNode::new`], but packs as set by `options`
+    ///
+    /// Items are kept `spacing` apart and `border` away from the container edges, see
+    /// [`BspaNode::placements`] for content positions; with a `page` height, items that
+    /// don't fit are left out, see [`pack_pages`]
+    ///
+    /// Panics if the margins leave no width or height to pack into
+    pub fn with_options<I: IntoIterator<Item = Rect>>(
+        avai_box: I,
+        w: u32,
+        n: usize,
+        f: f32,
+        options: Packing,
+    ) -> Self {
+        let (w, h) = options.extent(w);
+        let avai_box = avai_box.into_iter().map(|r| options.item(r));
+        Self {
+            border: options.border,
+            ..Self::build(avai_box, w, h, n, f)
+        }
+    }
+
+    fn build<I: IntoIterator<Item = Rect>>(
+        avai_box: I,
+        w: u32,
+        h: Option<u32>,
+        n: usize,
+        f: f32,
+    ) -> Self {
+        let items = avai_box.into_iter().collect::<Arc<[Rect]>>();
+        let mut avai_box = items.iter().fold(FxHashMap::default(), |mut acc, &c| {
+            *acc.entry(c).or_default() += 1;
+            acc
+        });
         avai_box.shrink_to_fit();
 
         let s = avai_box
             .iter()
             .map(|(k, v)| k.area() * *v as u64)
             .sum::<u64>();
-        let h = (s / w as u64) as u32;
+        let h = h.unwrap_or((s / w as u64) as u32);
 
         let avai_blk = avai_box
             .clone()
             .into_iter()
+            .flat_map(|(bt, bc)| {
+                let turns = match bt.is_rotatable() & (bt.w() != bt.h()) {
+                    true => [Some(bt), Some(bt.rotate())],
+                    false => [Some(bt), None],
+                };
+                turns.into_iter().flatten().map(move |bt| (bt, bc))
+            })
             .flat_map(|(bt, bc)| {
                 (1..=bc).flat_map(move |w| {
                     (1..=(bc / w)).map(move |l| {
@@ -84,7 +187,7 @@ impl BspaNode {
             .flat_map(|(a, b)| {
                 a.combine(b).into_iter().filter(|b| {
                     let boxes = b.list.iter().fold(FxHashMap::default(), |mut acc, c| {
-                        *acc.entry(c.item).or_default() += 1;
+                        *acc.entry(c.item.upright()).or_default() += 1;
                         acc
                     });
                     (b.w() <= w)
@@ -110,13 +213,63 @@ impl BspaNode {
             blocks: Default::default(),
             avai_box,
             avai_blk,
+            items,
+            border: 0,
         }
     }
 
+    /// Packed blocks, in packing coordinates and padded by spacing
     pub fn blocks(&self) -> &[Placement<RectGroup>] {
         &self.blocks
     }
 
+    /// Placed items without padding, in container coordinates including border
+    pub fn placements(&self) -> impl Iterator<Item = Placement<Rect>> + use<'_> {
+        self.placed().map(|p| Placement {
+            item: p.item.content(),
+            ..p
+        })
+    }
+
+    /// Placement of every input item by input index, like [`BspaNode::placements`]
+    ///
+    /// Items of equal size are assigned in placement order; `None` unless every item
+    /// is placed
+    pub fn layout(&self) -> Option<Vec<Placement<Rect>>> {
+        self.assign().into_iter().collect()
+    }
+
+    /// Placement of every input item by input index, `None` for items left out
+    fn assign(&self) -> Vec<Option<Placement<Rect>>> {
+        let mut placed = FxHashMap::<Rect, Vec<Placement<Rect>>>::default();
+        self.placed()
+            .rev()
+            .for_each(|p| placed.entry(p.item.upright()).or_default().push(p));
+
+        self.items
+            .iter()
+            .map(|k| {
+                let p = placed.get_mut(k)?.pop()?;
+                Some(Placement {
+                    item: p.item.content(),
+                    ..p
+                })
+            })
+            .collect()
+    }
+
+    /// Placed padded items in container coordinates
+    #[inline]
+    fn placed(&self) -> impl DoubleEndedIterator<Item = Placement<Rect>> + use<'_> {
+        self.blocks.iter().flat_map(|b| {
+            b.item.list.iter().map(|p| Placement {
+                x: self.border + b.x + p.x,
+                y: self.border + b.y + p.y,
+                item: p.item,
+            })
+        })
+    }
+
     #[inline]
     fn sel_space(&self) -> impl Iterator<Item = Placement<Rect>> + use<'_> {
         let mut buf = self.spaces.iter().collect::<Box<_>>();
@@ -134,7 +287,7 @@ impl BspaNode {
             let (s, l) = avai_box
                 .iter()
                 .fold((0u64, 0usize), |(mut s, mut l), (k, &v)| {
-                    let count = block.list.iter().filter(|&p| &p.item == k).count();
+                    let count = block.count(k);
                     let d = v - count;
                     l += d;
                     s += k.h() as u64 * d as u64;
@@ -190,18 +343,12 @@ impl BspaNode {
         block: RectGroup,
     ) -> &'a mut Self {
         let mut avai_box = self.avai_box.clone();
-        avai_box
-            .iter_mut()
-            .for_each(|(k, v)| *v -= block.list.iter().filter(|&p| &p.item == k).count());
+        avai_box.iter_mut().for_each(|(k, v)| *v -= block.count(k));
 
         let avai_blk = self
             .avai_blk
             .iter()
-            .filter(|b| {
-                avai_box
-                    .iter()
-                    .all(|(k, v)| *v >= b.list.iter().filter(|p| &p.item == k).count())
-            })
+            .filter(|b| avai_box.iter().all(|(k, v)| *v >= b.count(k)))
             .cloned()
             .collect::<Vec<_>>();
 
@@ -223,6 +370,8 @@ impl BspaNode {
             blocks,
             avai_box,
             avai_blk,
+            items: self.items.clone(),
+            border: self.border,
         })
     }
 }
@@ -5,7 +5,7 @@ mod tests;
 
 use std::mem::{self, MaybeUninit};
 
-use beamsrch::{BeamError, Node};
+use beamsrch::{BeamError, Context, Inflate, Node};
 #[cfg(feature = "rayon")]
 use rayon::prelude::*;
 use rustc_hash::FxHashMap;
@@ -20,6 +20,7 @@ impl<const B: usize> Node<B> for BspaNode {
     #[cfg(not(feature = "rayon"))]
     fn expand<'a, I: Iterator<Item = &'a mut MaybeUninit<Self>>>(
         &'a self,
+        _: &Context,
         iter: I,
     ) -> Result<usize, BeamError> {
         let (space, block_pool) = self.prepare::<B>()?;
@@ -37,6 +38,7 @@ impl<const B: usize> Node<B> for BspaNode {
     #[cfg(feature = "rayon")]
     fn expand<'a, I: IndexedParallelIterator<Item = &'a mut MaybeUninit<Self>>>(
         &'a self,
+        _: &Context,
         iter: I,
     ) -> Result<usize, BeamError> {
         let (space, block_pool) = self.prepare::<B>()?;
@@ -51,7 +53,7 @@ impl<const B: usize> Node<B> for BspaNode {
         Ok(iter.zip(block_pool).map(fill_op).count())
     }
 
-    fn evaluate(&self) -> u64 {
+    fn evaluate(&self, _: &Context) -> u64 {
         #[inline]
         fn avg_high(avai_box: &FxHashMap<Rect, usize>) -> f64 {
             let (s, l) = avai_box
@@ -79,6 +81,14 @@ impl<const B: usize> Node<B> for BspaNode {
         (cast(heuristic) as u64) << u32::BITS | cast(self.area() - self.fill_area()) as u64
     }
 
+    fn estimate(&self) -> Option<usize> {
+        self.prepare::<B>()
+            .ok()
+            .map(|(_, block_pool)| block_pool.len())
+    }
+}
+
+impl<const B: usize> Inflate<B> for BspaNode {
     fn inflate(&mut self) {
         let xmax = self
             .spaces
@@ -155,10 +165,4 @@ impl<const B: usize> Node<B> for BspaNode {
         #[cfg(debug_assertions)]
         assert::assert_node_inflate(self);
     }
-
-    fn estimate(&self) -> Option<usize> {
-        self.prepare::<B>()
-            .ok()
-            .map(|(_, block_pool)| block_pool.len())
-    }
 }
@@ -24,7 +24,7 @@ pub fn assert_node_expand(x: &BspaNode) {
     debug_assert_eq!(
         x.avai_blk.iter().find(|b| {
             x.avai_box.iter().any(|(k, v)| {
-                let d = b.list.iter().filter(|p| &p.item == k).count();
+                let d = b.count(k);
                 v.checked_sub(d).is_none()
             })
         }),
@@ -33,11 +33,9 @@ pub fn assert_node_expand(x: &BspaNode) {
     // avai_blk covers avai_box variations
     debug_assert_eq!(
         x.avai_box.iter().find(|&(k, v)| {
-            !(0..=*v).skip(1).all(|n| {
-                x.avai_blk
-                    .iter()
-                    .any(|b| b.list.iter().filter(|p| &p.item == k).count() == n)
-            })
+            !(0..=*v)
+                .skip(1)
+                .all(|n| x.avai_blk.iter().any(|b| b.count(k) == n))
         }),
         None,
         "\navai_box: {:?}\navai_blk: {:?}",
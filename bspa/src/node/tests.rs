@@ -1,9 +1,16 @@
-use beamsrch::Node;
+use beamsrch::{Inflate, Node, testing};
+use itertools::Itertools;
 
-use crate::{Area, BspaNode, types::*};
+use crate::{Area, BspaNode, Packing, types::*};
 
 const SIDE: u32 = 8;
 const PADD: u32 = 4;
+const ROTATION: Packing = Packing {
+    page: None,
+    spacing: 0,
+    border: 0,
+    rotation: true,
+};
 
 #[test]
 fn basic_beam_functions() {
@@ -55,9 +62,11 @@ fn basic_beam_functions() {
         }],
         avai_box: tmp.avai_box,
         avai_blk: tmp.avai_blk,
+        items: tmp.items,
+        border: 0,
     };
 
-    Node::<0>::inflate(&mut node);
+    Inflate::<0>::inflate(&mut node);
 
     let xmax = node
         .spaces
@@ -91,3 +100,136 @@ fn basic_beam_functions() {
         ]
     );
 }
+
+#[test]
+fn node_conformance() {
+    const BB: usize = 16;
+    const RECT_0: Rect = Rect::new(SIDE, SIDE);
+    const RECT_1: Rect = Rect::new(SIDE, PADD);
+
+    let node = BspaNode::new([RECT_0, RECT_1].repeat(5), SIDE * 3, 16, 1.0);
+    let leaf = testing::check::<BB, _>(node, BB, usize::MAX).unwrap();
+
+    assert!(!leaf.blocks().is_empty());
+    testing::check_inflate::<BB, _>(leaf).unwrap();
+}
+
+#[test]
+fn rotated_placement() {
+    const BB: usize = 16;
+    const RECT_0: Rect = Rect::new(SIDE * 2, SIDE);
+
+    // too wide for the strip unless rotated
+    let node = BspaNode::new([RECT_0].repeat(2), SIDE, 16, 1.0);
+    let leaf = testing::check::<BB, _>(node, BB, usize::MAX).unwrap();
+    assert!(!Node::<BB>::has_fulfilled(&leaf));
+
+    let node = BspaNode::with_options([RECT_0].repeat(2), SIDE, 16, 1.0, ROTATION);
+    let leaf = testing::check::<BB, _>(node, BB, usize::MAX).unwrap();
+    assert!(Node::<BB>::has_fulfilled(&leaf));
+
+    let rects = leaf
+        .blocks()
+        .iter()
+        .flat_map(|b| {
+            b.item
+                .list
+                .iter()
+                .map(move |p| (b.x + p.x, b.y + p.y, p.item))
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(rects.len(), 2);
+    assert!(rects.iter().all(|(x, _, r)| {
+        r.is_rotated() & (r.upright() == RECT_0.rotatable()) & (x + r.w() <= SIDE)
+    }));
+}
+
+#[test]
+fn margin_placement() {
+    const BB: usize = 16;
+    const RECT_0: Rect = Rect::new(SIDE, SIDE);
+    const W: u32 = SIDE * 2 + PADD * 3;
+
+    let margins = Packing {
+        spacing: PADD,
+        border: PADD,
+        ..Default::default()
+    };
+    let node = BspaNode::with_options([RECT_0].repeat(4), W, 16, 1.0, margins);
+    let leaf = testing::check::<BB, _>(node, BB, usize::MAX).unwrap();
+    assert!(Node::<BB>::has_fulfilled(&leaf));
+
+    let rects = leaf.placements().collect::<Vec<_>>();
+    assert_eq!(rects.len(), 4);
+    assert!(rects.iter().all(|p| {
+        (p.item == RECT_0) & (p.x >= PADD) & (p.y >= PADD) & (p.x + p.item.w() <= W - PADD)
+    }));
+    // content grown by spacing on one side never reaches another content
+    assert!(rects.iter().tuple_combinations().all(|(l, r)| {
+        let grow = |p: &Placement<Rect>| Placement {
+            x: p.x,
+            y: p.y,
+            item: p.item.padded(PADD),
+        };
+        !grow(l).overlaps(r) & !grow(r).overlaps(l)
+    }));
+}
+
+#[test]
+#[should_panic(expected = "leave no width")]
+fn margins_exceed_width() {
+    let margins = Packing {
+        border: SIDE,
+        ..Default::default()
+    };
+    BspaNode::with_options([Rect::new(SIDE, SIDE)], SIDE, 16, 1.0, margins);
+}
+
+#[test]
+#[should_panic(expected = "leave no width")]
+fn margins_overflow_width() {
+    let margins = Packing {
+        spacing: 1,
+        ..Default::default()
+    };
+    BspaNode::with_options([Rect::new(SIDE, SIDE)], u32::MAX, 16, 1.0, margins);
+}
+
+#[test]
+#[should_panic(expected = "leave no height")]
+fn margins_exceed_page() {
+    let margins = Packing {
+        page: Some(SIDE),
+        border: SIDE / 2,
+        ..Default::default()
+    };
+    BspaNode::with_options([Rect::new(SIDE, SIDE)], SIDE * 2, 16, 1.0, margins);
+}
+
+#[test]
+fn item_layout() {
+    const BB: usize = 16;
+    const ITEMS: [Rect; 4] = [
+        Rect::new(SIDE, SIDE),
+        Rect::new(SIDE * 2, PADD),
+        Rect::new(SIDE, SIDE),
+        Rect::new(SIDE * 2, PADD),
+    ];
+
+    let node = BspaNode::with_options(ITEMS, SIDE * 2, 16, 1.0, ROTATION);
+    assert_eq!(node.layout(), None);
+
+    let leaf = testing::check::<BB, _>(node, BB, usize::MAX).unwrap();
+    assert!(Node::<BB>::has_fulfilled(&leaf));
+
+    let layout = leaf.layout().unwrap();
+    assert_eq!(layout.len(), ITEMS.len());
+    assert!(
+        ITEMS
+            .iter()
+            .zip(&layout)
+            .all(|(r, p)| p.item.upright() == r.rotatable())
+    );
+    assert!(layout.iter().all(|p| leaf.placements().contains(p)));
+    assert!(layout.iter().all_unique());
+}
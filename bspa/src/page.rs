@@ -0,0 +1,169 @@
+use std::cmp::Reverse;
+
+use beamsrch::Beam;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use crate::{Area, BspaNode, Packing, Placement, Rect};
+
+/// Fixed-size container filled by [`pack_pages`]
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Page {
+    /// Input indices of items on this page
+    pub items: Vec<usize>,
+    /// Placements of `items`, by position
+    pub placements: Vec<Placement<Rect>>,
+}
+
+/// Pack `avai_box` into as few `w` wide pages as found, returns the pages and input
+/// indices of items no empty page can take
+///
+/// Pages are `options.page` high, or as high as the items left over fill `w` if unset.
+/// Every page is filled by a [`Beam`] from the items left over by previous pages,
+/// keeping the slot that placed the most area
+///
+/// Panics if the margins of `options` leave no width or height to pack into
+pub fn pack_pages<const W: usize, const B: usize, I>(
+    avai_box: I,
+    w: u32,
+    n: usize,
+    f: f32,
+    options: Packing,
+) -> (Vec<Page>, Vec<usize>)
+where
+    I: IntoIterator<Item = Rect>,
+{
+    let items = avai_box.into_iter().collect::<Vec<_>>();
+    let (pw, ph) = options.extent(w);
+    let fits = |r: &Rect| {
+        let within = |r: &Rect| (r.w() <= pw) & (r.h() <= ph.unwrap_or(u32::MAX));
+        let r = options.item(*r);
+        within(&r) | (r.is_rotatable() & within(&r.rotate()))
+    };
+    let (mut left, mut oversized) = (0..items.len()).partition::<Vec<_>, _>(|&i| fits(&items[i]));
+    let mut pages = Vec::new();
+
+    while !left.is_empty() {
+        let root = BspaNode::with_options(left.iter().map(|&i| items[i]), w, n, f, options);
+        let Ok(mut beam) = Beam::<W, B, _>::try_from_root(root) else {
+            break;
+        };
+        while !beam.has_fulfilled() && beam.cycle().is_ok() {}
+
+        let assigned = beam
+            .frontier()
+            .map(|(i, k, node)| (Reverse(node.fill_area()), k, i, node))
+            .min_by_key(|&(a, k, i, _)| (a, k, i))
+            .map(|(.., node)| node.assign())
+            .unwrap_or_default();
+
+        let mut page = Page::default();
+        let mut rest = Vec::with_capacity(left.len());
+        left.into_iter().zip(assigned).for_each(|(i, p)| match p {
+            Some(p) => {
+                page.items.push(i);
+                page.placements.push(p);
+            }
+            None => rest.push(i),
+        });
+        left = rest;
+        if page.items.is_empty() {
+            break;
+        }
+        pages.push(page);
+    }
+
+    oversized.append(&mut left);
+    oversized.sort_unstable();
+    (pages, oversized)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RECT_S: Rect = Rect::new(8, 8);
+    const RECT_L: Rect = Rect::new(16, 16);
+
+    const PAGE: Packing = Packing {
+        page: Some(16),
+        spacing: 0,
+        border: 0,
+        rotation: false,
+    };
+
+    #[test]
+    fn pages() {
+        const BB: usize = 16;
+
+        let (pages, left) = pack_pages::<BB, BB, _>([RECT_S].repeat(4), 16, 16, 1.0, PAGE);
+        assert_eq!((pages.len(), left.len()), (1, 0));
+        assert_eq!(pages[0].items, [0, 1, 2, 3]);
+
+        let items = [RECT_S, RECT_L, RECT_S, RECT_S, RECT_S];
+        let (pages, left) = pack_pages::<BB, BB, _>(items, 16, 16, 1.0, PAGE);
+        assert_eq!((pages.len(), left.len()), (2, 0));
+
+        let mut placed = pages.iter().flat_map(|p| &p.items).collect::<Vec<_>>();
+        placed.sort();
+        assert!(placed.into_iter().copied().eq(0..items.len()));
+        assert!(pages.iter().all(|p| {
+            p.items.iter().zip(&p.placements).all(|(&i, q)| {
+                (q.item == items[i]) & (q.x + q.item.w() <= 16) & (q.y + q.item.h() <= 16)
+            })
+        }));
+
+        // oversized items are left out, the rest is still paged
+        let items = [
+            RECT_S,
+            Rect::new(32, 8),
+            RECT_S,
+            Rect::new(8, 32).rotatable(),
+        ];
+        let (pages, left) = pack_pages::<BB, BB, _>(items, 16, 16, 1.0, PAGE);
+        assert_eq!(pages.len(), 1);
+        assert_eq!(pages[0].items.len(), 2);
+        assert_eq!(left, [1, 3]);
+
+        // a filled page is preferred over more items placed
+        let items = [Rect::new(8, 4), Rect::new(16, 12), Rect::new(8, 4)]
+            .into_iter()
+            .chain([Rect::new(8, 4)].repeat(2))
+            .collect::<Vec<_>>();
+        let (pages, _) = pack_pages::<BB, BB, _>(items, 16, 16, 1.0, PAGE);
+        assert!(pages[0].items.contains(&1));
+        assert_eq!(pages[0].items.len(), 3);
+    }
+
+    #[test]
+    fn margin_pages() {
+        const BB: usize = 16;
+        const SPACING: u32 = 2;
+        const BORDER: u32 = 3;
+
+        // 2 by 2 items padded to 10 fit into 24 - 2 * 3 + 2
+        let options = Packing {
+            page: Some(24),
+            spacing: SPACING,
+            border: BORDER,
+            ..PAGE
+        };
+        let (pages, left) = pack_pages::<BB, BB, _>([RECT_S].repeat(4), 24, 16, 1.0, options);
+        assert_eq!((pages.len(), left.len()), (1, 0));
+        assert!(pages[0].placements.iter().all(|p| {
+            (p.item == RECT_S)
+                & (p.x >= BORDER)
+                & (p.y >= BORDER)
+                & (p.x + p.item.w() <= 24 - BORDER)
+                & (p.y + p.item.h() <= 24 - BORDER)
+        }));
+
+        // a page shorter by one takes a row only
+        let options = Packing {
+            page: Some(23),
+            ..options
+        };
+        let (pages, left) = pack_pages::<BB, BB, _>([RECT_S].repeat(4), 24, 16, 1.0, options);
+        assert_eq!((pages.len(), left.len()), (2, 0));
+    }
+}
@@ -7,6 +7,12 @@ pub struct Rect {
     w: u32,
     h: u32,
     s: u64,
+    /// Spacing included in `w` and `h` past the content's right and bottom edges
+    pad: u32,
+    /// May be placed rotated by 90°
+    rotatable: bool,
+    /// Placed rotated by 90°, `w` and `h` are swapped from the input item
+    rotated: bool,
 }
 
 impl Rect {
@@ -15,6 +21,71 @@ impl Rect {
             w,
             h,
             s: w as u64 * h as u64,
+            pad: 0,
+            rotatable: false,
+            rotated: false,
+        }
+    }
+
+    /// Reserve `pad` past the right and bottom edges, keeping padded items apart
+    pub const fn padded(self, pad: u32) -> Self {
+        let Self { w, h, .. } = self.content();
+        Self {
+            w: w + pad,
+            h: h + pad,
+            s: (w + pad) as u64 * (h + pad) as u64,
+            pad,
+            ..self
+        }
+    }
+
+    /// Item without padding, see [`Rect::padded`]
+    pub const fn content(self) -> Self {
+        let (w, h) = (self.w - self.pad, self.h - self.pad);
+        Self {
+            w,
+            h,
+            s: w as u64 * h as u64,
+            pad: 0,
+            ..self
+        }
+    }
+
+    /// Allow placing this item rotated by 90°
+    pub const fn rotatable(self) -> Self {
+        Self {
+            rotatable: true,
+            ..self
+        }
+    }
+
+    #[inline]
+    pub const fn is_rotatable(&self) -> bool {
+        self.rotatable
+    }
+
+    /// Whether placed rotated by 90° relative to the input item
+    #[inline]
+    pub const fn is_rotated(&self) -> bool {
+        self.rotated
+    }
+
+    /// Rotate by 90°, swapping `w` and `h`
+    pub const fn rotate(self) -> Self {
+        Self {
+            w: self.h,
+            h: self.w,
+            rotated: !self.rotated,
+            ..self
+        }
+    }
+
+    /// Item in its input orientation
+    #[inline]
+    pub const fn upright(self) -> Self {
+        match self.rotated {
+            true => self.rotate(),
+            false => self,
         }
     }
 }
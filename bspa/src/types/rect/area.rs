@@ -54,5 +54,9 @@ mod tests {
     fn area() {
         assert_eq!(RECT_S.area(), 64);
         assert_eq!(RECT_S.fill_area(), 64);
+
+        let padded = RECT_S.padded(4).rotate();
+        assert_eq!((padded.w(), padded.area()), (12, 144));
+        assert_eq!(padded.content().upright(), RECT_S);
     }
 }
@@ -22,6 +22,15 @@ impl RectGroup {
         space.area() - self.area() + avg_high.round() as u64
     }
 
+    /// Count of `item` in the group, in either orientation
+    #[inline]
+    pub fn count(&self, item: &Rect) -> usize {
+        self.list
+            .iter()
+            .filter(|p| &p.item.upright() == item)
+            .count()
+    }
+
     pub fn combine(self, other: Self) -> [Self; 2] {
         let w = self.w();
         let h = self.h();
@@ -28,7 +28,7 @@ fn simple_atlas_packing() {
     common::save_pg(
         &ITEMS.iter().copied().collect(),
         beam.nodes()
-            .min_by_key(|n| Node::<BB>::evaluate(*n))
+            .min_by_key(|n| Node::<BB>::evaluate(*n, &Default::default()))
             .unwrap()
             .blocks(),
         "simple_blocks.png",
@@ -132,7 +132,7 @@ fn varied_atlas_packing() {
     common::save_pg(
         &ITEMS.iter().copied().collect(),
         beam.nodes()
-            .min_by_key(|n| Node::<BB>::evaluate(*n))
+            .min_by_key(|n| Node::<BB>::evaluate(*n, &Default::default()))
             .unwrap()
             .blocks(),
         "varied_blocks.png",
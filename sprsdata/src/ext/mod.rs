@@ -1,3 +1,5 @@
+//! Memory-mapped buffers backing `memmap2` models
+
 #[cfg(feature = "memmap2")]
 mod memmap2;
 
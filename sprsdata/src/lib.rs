@@ -1,4 +1,4 @@
-mod ext;
+pub mod ext;
 mod map;
 mod set;
 